@@ -1,6 +1,8 @@
+use char_trie::dict::{self, Format};
+use char_trie::segment::DagSegmenter;
 use char_trie::Trie;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
 
 pub fn main() {
     // let mut trie = Trie::default();
@@ -8,16 +10,8 @@ pub fn main() {
 
     let start = std::time::Instant::now();
 
-    for line in BufReader::new(File::open("dict/default.dic").unwrap()).lines() {
-        let line = line.unwrap();
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() == 3 {
-            trie.insert(
-                parts[0],
-                (parts[1].to_string(), parts[2].parse::<i32>().unwrap()),
-            );
-        }
-    }
+    let reader = BufReader::new(File::open("dict/default.dic").unwrap());
+    dict::read_into(&mut trie, reader, Format::Ansj, dict::ansj_value).unwrap();
 
     trie.insert("中国人", (String::from("ud"), 10000));
 
@@ -31,4 +25,9 @@ pub fn main() {
 
     let c: Vec<_> = trie.iter_all(text).map(|t| t.0).collect();
     println!("{:?}", c);
+
+    // 以词频的对数作为权重，做最大概率切分
+    let segmenter = DagSegmenter::new(&trie, |(_, freq)| (*freq as f64 + 1.0).ln());
+    let c: Vec<_> = segmenter.segment(text).into_iter().map(|t| t.0).collect();
+    println!("{:?}", c);
 }
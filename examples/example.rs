@@ -1,20 +1,23 @@
 use char_trie::Trie;
 
 pub fn main() {
-    let mut trie = Trie::default();
-
-    trie.insert("中国人", String::from("ud"));
-    trie.insert("中国", String::from("ud"));
-    trie.insert("我", String::from("ud"));
-    trie.insert("是", String::from("ud"));
-    trie.insert("爱", String::from("ud"));
-    trie.insert("北京", String::from("ud"));
-    trie.insert("天安门", String::from("ud"));
-    trie.insert("天安", String::from("ud"));
-    trie.insert("安门", String::from("ud"));
-    trie.insert("上", String::from("ud"));
-    trie.insert("太阳", String::from("ud"));
-    trie.insert("升", String::from("ud"));
+    let trie: Trie<String> = [
+        "中国人",
+        "中国",
+        "我",
+        "是",
+        "爱",
+        "北京",
+        "天安门",
+        "天安",
+        "安门",
+        "上",
+        "太阳",
+        "升",
+    ]
+    .into_iter()
+    .map(|word| (word, String::from("ud")))
+    .collect();
 
     let text = "我爱北京天安门，天安门上太阳升。我是中国人，我爱中国。";
 
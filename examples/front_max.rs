@@ -1,21 +1,9 @@
-use char_trie::Trie;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use char_trie::dict::{self, Format};
 
 pub fn main() {
-    let mut trie = Trie::default();
     let start = std::time::Instant::now();
 
-    for line in BufReader::new(File::open("dict/default.dic").unwrap()).lines() {
-        let line = line.unwrap();
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() == 3 {
-            trie.insert(
-                parts[0],
-                (parts[1].to_string(), parts[2].parse::<i32>().unwrap()),
-            );
-        }
-    }
+    let trie = dict::load_file("dict/default.dic", Format::Ansj, dict::ansj_value).unwrap();
 
     println!("load dict use {:?}", start.elapsed());
 
@@ -0,0 +1,224 @@
+//! Aho-Corasick 自动机，由 Trie 树编译而来，一次线性扫描完成全词匹配
+//!
+//! 输出和 [`Trie::iter_all`] 完全一致：按起始位置、再按结束位置排序
+//!
+//! ```rust
+//! use char_trie::ac::AcAutomaton;
+//! use char_trie::Trie;
+//! let mut trie = Trie::default();
+//! trie.insert("中国人", "cns");
+//! trie.insert("中国", "cn");
+//! trie.insert("国人", "gr");
+//! let ac = AcAutomaton::new(&trie);
+//! let text = "我是中国人";
+//! let tokens: Vec<_> = ac.iter_all(text).collect();
+//! assert_eq!(tokens, trie.iter_all(text).collect::<Vec<_>>());
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::str::CharIndices;
+
+use crate::normalize::Normalize;
+use crate::tokenizer::Token;
+use crate::{Status, Trie};
+
+const ROOT: u32 = 0;
+const NONE: u32 = u32::MAX;
+
+struct State<'a, T> {
+    /// 按字符排序的转移
+    goto: Vec<(char, u32)>,
+    /// 失败指针，指向当前路径最长的、同时也是 Trie 中路径的真后缀
+    fail: u32,
+    /// 输出指针，沿失败指针找到的最近一个词
+    output: u32,
+    /// 从根节点到当前状态路径的字符数
+    depth: usize,
+    value: Option<&'a T>,
+}
+
+impl<T> State<'_, T> {
+    fn goto(&self, c: char) -> Option<u32> {
+        self.goto
+            .binary_search_by(|(k, _)| k.cmp(&c))
+            .ok()
+            .map(|i| self.goto[i].1)
+    }
+}
+
+pub struct AcAutomaton<'a, T> {
+    states: Vec<State<'a, T>>,
+    max_depth: usize,
+    normalize: Normalize,
+}
+
+impl<'a, T> AcAutomaton<'a, T> {
+    /// 由 Trie 树编译自动机，值引用自原来的 Trie 树
+    pub fn new(trie: &'a Trie<T>) -> Self {
+        let mut states = vec![State {
+            goto: Vec::new(),
+            fail: ROOT,
+            output: NONE,
+            depth: 0,
+            value: None,
+        }];
+        let mut max_depth = 0;
+
+        // 广度优先编号，保证计算失败指针时父节点的失败指针已经确定
+        let mut queue = VecDeque::from([(trie, ROOT)]);
+        while let Some((node, id)) = queue.pop_front() {
+            for child in node.leafs.iter() {
+                let child_id = states.len() as u32;
+                let depth = states[id as usize].depth + 1;
+                max_depth = max_depth.max(depth);
+                let value = match child.status {
+                    Status::Not => None,
+                    Status::End | Status::LastEnd => child.value.as_ref(),
+                };
+                states.push(State {
+                    goto: Vec::new(),
+                    fail: ROOT,
+                    output: NONE,
+                    depth,
+                    value,
+                });
+                states[id as usize].goto.push((child.c, child_id));
+                queue.push_back((child, child_id));
+            }
+        }
+
+        for id in 0..states.len() {
+            for i in 0..states[id].goto.len() {
+                let (c, child) = states[id].goto[i];
+                let fail = if id == ROOT as usize {
+                    ROOT
+                } else {
+                    let mut f = states[id].fail;
+                    loop {
+                        if let Some(next) = states[f as usize].goto(c) {
+                            break next;
+                        }
+                        if f == ROOT {
+                            break ROOT;
+                        }
+                        f = states[f as usize].fail;
+                    }
+                };
+                let output = if states[fail as usize].value.is_some() {
+                    fail
+                } else {
+                    states[fail as usize].output
+                };
+                let state = &mut states[child as usize];
+                state.fail = fail;
+                state.output = output;
+            }
+        }
+
+        Self {
+            states,
+            max_depth,
+            normalize: trie.normalize,
+        }
+    }
+
+    /// 全词匹配，结果与 [`Trie::iter_all`] 相同
+    pub fn iter_all<'b>(&'b self, text: &'b str) -> AcTokenizer<'b, T> {
+        AcTokenizer::new(self, text)
+    }
+
+    fn next_state(&self, mut id: u32, c: char) -> u32 {
+        let c = self.normalize.apply(c);
+        loop {
+            if let Some(next) = self.states[id as usize].goto(c) {
+                return next;
+            }
+            if id == ROOT {
+                return ROOT;
+            }
+            id = self.states[id as usize].fail;
+        }
+    }
+}
+
+/// 自动机的全词匹配结果，匹配是按结束位置找到的，
+/// 先缓存起来，确认不会再有更靠前的词后按起始位置输出
+pub struct AcTokenizer<'a, T> {
+    automaton: &'a AcAutomaton<'a, T>,
+    text: &'a str,
+    chars: CharIndices<'a>,
+    state: u32,
+    /// 最近 max_depth 个字符的起始位置，归一化后字符的字节长度可能变化，匹配位置从这里取
+    starts: VecDeque<usize>,
+    /// 当前状态对应路径的起始位置，之后的匹配都不会早于它开始
+    frontier: usize,
+    pending: BinaryHeap<Reverse<(usize, usize, u32)>>,
+}
+
+impl<'a, T> AcTokenizer<'a, T> {
+    pub fn new(automaton: &'a AcAutomaton<'a, T>, text: &'a str) -> Self {
+        Self {
+            automaton,
+            text,
+            chars: text.char_indices(),
+            state: ROOT,
+            starts: VecDeque::with_capacity(automaton.max_depth + 1),
+            frontier: 0,
+            pending: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for AcTokenizer<'a, T> {
+    type Item = Token<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(&Reverse((start, end, id))) = self.pending.peek() {
+                if start <= self.frontier {
+                    self.pending.pop();
+                    let value = self.automaton.states[id as usize].value;
+                    return Some((&self.text[start..end], (start, end), value));
+                }
+            }
+
+            let Some((i, c)) = self.chars.next() else {
+                // 文本结束，剩下的匹配全部输出
+                self.frontier = self.text.len();
+                let Reverse((start, end, id)) = self.pending.pop()?;
+                let value = self.automaton.states[id as usize].value;
+                return Some((&self.text[start..end], (start, end), value));
+            };
+
+            let states = &self.automaton.states;
+            let end = i + c.len_utf8();
+            self.starts.push_back(i);
+            if self.starts.len() > self.automaton.max_depth {
+                self.starts.pop_front();
+            }
+            // depth 个字符之前的起始位置
+            let start = |depth: usize| {
+                if depth == 0 {
+                    end
+                } else {
+                    self.starts[self.starts.len() - depth]
+                }
+            };
+
+            self.state = self.automaton.next_state(self.state, c);
+            self.frontier = start(states[self.state as usize].depth);
+
+            let mut id = if states[self.state as usize].value.is_some() {
+                self.state
+            } else {
+                states[self.state as usize].output
+            };
+            while id != NONE {
+                let state = &states[id as usize];
+                self.pending.push(Reverse((start(state.depth), end, id)));
+                id = state.output;
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::{Error, Result};
+
+/// 字符等价表，如繁体到简体的映射。
+/// 匹配时文本中的字符同时按原字符和转换后的字符在 Trie 树中查找，两种写法都是词时取与原文写法相同的词，
+/// 返回的 Token 仍然指向原文
+/// # Examples
+/// ```rust
+/// use char_trie::charmap::CharMap;
+/// use char_trie::Trie;
+/// let mut map = CharMap::new();
+/// map.insert('國', '国');
+/// let mut trie = Trie::default();
+/// trie.insert("中国", "cn");
+/// trie.insert("中国人", "cns");
+/// let tokens: Vec<_> = trie.iter_all("中國人").equivalence(&map).map(|t| t.0).collect();
+/// assert_eq!(tokens, vec!["中國", "中國人"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CharMap {
+    map: HashMap<char, char>,
+}
+
+impl CharMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一个映射，返回 from 原来的映射
+    pub fn insert(&mut self, from: char, to: char) -> Option<char> {
+        self.map.insert(from, to)
+    }
+
+    pub fn get(&self, c: char) -> Option<char> {
+        self.map.get(&c).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// 从文本读取等价表，每行为 `原字符 目标字符`，用制表符或空格分隔，
+    /// 目标字符有多个候选时取第一个（与 OpenCC 的 TSCharacters.txt 格式兼容），
+    /// 空行和 `#` 开头的行忽略，格式错误时返回带行号的 [`Error::Malformed`]
+    /// # Examples
+    /// ```rust
+    /// use char_trie::charmap::CharMap;
+    /// let map = CharMap::from_reader("# 繁简\n國\t国\n乾\t乾 干\n".as_bytes()).unwrap();
+    /// assert_eq!(map.get('國'), Some('国'));
+    /// assert_eq!(map.get('乾'), Some('乾'));
+    /// assert!(CharMap::from_reader("中國\t中国\n".as_bytes()).is_err());
+    /// ```
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut map = CharMap::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            match (single_char(parts.next()), single_char(parts.next())) {
+                (Some(from), Some(to)) => {
+                    map.insert(from, to);
+                }
+                _ => {
+                    return Err(Error::malformed(
+                        i + 1,
+                        format!("expected two single characters: {}", line),
+                    ))
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    /// 从文件读取等价表，格式参见 [`CharMap::from_reader`]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        Self::from_reader(BufReader::new(File::open(path)?)).map_err(|e| e.in_file(path))
+    }
+}
+
+fn single_char(s: Option<&str>) -> Option<char> {
+    let mut cs = s?.chars();
+    match (cs.next(), cs.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+impl FromIterator<(char, char)> for CharMap {
+    fn from_iter<I: IntoIterator<Item = (char, char)>>(iter: I) -> Self {
+        Self {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<(char, char)> for CharMap {
+    fn extend<I: IntoIterator<Item = (char, char)>>(&mut self, iter: I) {
+        self.map.extend(iter);
+    }
+}
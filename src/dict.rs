@@ -0,0 +1,203 @@
+//! 词典文件加载，支持 ansj、jieba、纯词表和 CSV 格式
+//!
+//! 每行第一列为词，其余列交给值解析函数转换为词典的值，
+//! 解析失败时返回带行号的 [`Error::Malformed`]，不会 panic
+//!
+//! ```rust
+//! use char_trie::dict::{self, Format};
+//! use char_trie::Trie;
+//! let text = "中国\tns\t100\n中国人\tn\t20\n";
+//! let mut trie = Trie::default();
+//! let n = dict::read_into(&mut trie, text.as_bytes(), Format::Ansj, dict::ansj_value).unwrap();
+//! assert_eq!(n, 2);
+//! assert_eq!(trie.get("中国人"), Some(&("n".to_string(), 20)));
+//! ```
+
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::{self, Error};
+use crate::Trie;
+
+/// 词典文件格式，空行会被忽略
+/// # Examples
+/// ```rust
+/// use char_trie::dict::{self, Format};
+/// use char_trie::Trie;
+/// let text = "中国,cn\n\"你好,世界\",\"say \"\"hi\"\"\"\n";
+/// let mut trie = Trie::default();
+/// dict::read_into(&mut trie, text.as_bytes(), Format::Csv, |f| Ok(f[0].to_string())).unwrap();
+/// assert_eq!(trie.get("你好,世界"), Some(&"say \"hi\"".to_string()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// ansj 格式，制表符分隔：`词 词性 词频`
+    Ansj,
+    /// jieba 格式，空白分隔：`词 词频 [词性]`
+    Jieba,
+    /// 每行一个词，没有其他列
+    Words,
+    /// 逗号分隔，字段可以用双引号包围，引号内的 `""` 表示一个双引号
+    Csv,
+}
+
+impl Format {
+    /// 把一行拆分为字段，第一个字段为词
+    fn split<'a>(&self, line: &'a str) -> Result<Vec<Cow<'a, str>>, String> {
+        Ok(match self {
+            Format::Ansj => line.split('\t').map(Cow::Borrowed).collect(),
+            Format::Jieba => line.split_whitespace().map(Cow::Borrowed).collect(),
+            Format::Words => vec![Cow::Borrowed(line.trim())],
+            Format::Csv => split_csv(line)?,
+        })
+    }
+}
+
+fn split_csv(line: &str) -> Result<Vec<Cow<'_, str>>, String> {
+    let mut fields = Vec::new();
+    let mut rest = line;
+    loop {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            let mut field = String::new();
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((i, '"')) => {
+                        if quoted[i + 1..].starts_with('"') {
+                            field.push('"');
+                            chars.next();
+                        } else {
+                            break i + 1;
+                        }
+                    }
+                    Some((_, c)) => field.push(c),
+                    None => return Err("unterminated quoted field".to_string()),
+                }
+            };
+            fields.push(Cow::Owned(field));
+            rest = &quoted[end..];
+            match rest.strip_prefix(',') {
+                Some(next) => rest = next,
+                None if rest.is_empty() => return Ok(fields),
+                None => return Err("unexpected character after quoted field".to_string()),
+            }
+        } else {
+            match rest.split_once(',') {
+                Some((field, next)) => {
+                    fields.push(Cow::Borrowed(field));
+                    rest = next;
+                }
+                None => {
+                    fields.push(Cow::Borrowed(rest));
+                    return Ok(fields);
+                }
+            }
+        }
+    }
+}
+
+/// ansj 格式的值解析：(词性, 词频)
+pub fn ansj_value(fields: &[&str]) -> Result<(String, i32), String> {
+    match fields {
+        [nature, freq, ..] => Ok((nature.to_string(), parse_number(freq)?)),
+        _ => Err(format!(
+            "expected nature and frequency, found {} fields",
+            fields.len()
+        )),
+    }
+}
+
+/// jieba 格式的值解析：(词频, 词性)，词性可以省略
+pub fn jieba_value(fields: &[&str]) -> Result<(u64, Option<String>), String> {
+    match fields {
+        [freq, tag @ ..] => Ok((parse_number(freq)?, tag.first().map(|t| t.to_string()))),
+        _ => Err("expected frequency".to_string()),
+    }
+}
+
+/// 忽略词以外的列，用于纯词表
+pub fn no_value(_: &[&str]) -> Result<(), String> {
+    Ok(())
+}
+
+fn parse_number<N: std::str::FromStr>(s: &str) -> Result<N, String>
+where
+    N::Err: std::fmt::Display,
+{
+    s.trim()
+        .parse()
+        .map_err(|e| format!("invalid number {:?}: {}", s, e))
+}
+
+/// 按格式读取词典，批量插入到 trie 中，返回读取的词数。
+/// parser 接收词以外的各列，返回词的值
+///
+/// 格式错误时返回带行号的 [`Error::Malformed`]，trie 不会被修改
+/// # Examples
+/// ```rust
+/// use char_trie::dict::{self, Format};
+/// use char_trie::Trie;
+/// let mut trie = Trie::default();
+/// let text = "中国 100 ns\n中国人 20\n";
+/// dict::read_into(&mut trie, text.as_bytes(), Format::Jieba, dict::jieba_value).unwrap();
+/// assert_eq!(trie.get("中国"), Some(&(100, Some("ns".to_string()))));
+/// assert_eq!(trie.get("中国人"), Some(&(20, None)));
+///
+/// let err = dict::read_into(&mut trie, "美国 x\n".as_bytes(), Format::Jieba, dict::jieba_value);
+/// assert_eq!(
+///     err.unwrap_err().to_string(),
+///     "line 1: invalid number \"x\": invalid digit found in string"
+/// );
+/// ```
+pub fn read_into<T, R, F>(
+    trie: &mut Trie<T>,
+    reader: R,
+    format: Format,
+    mut parser: F,
+) -> error::Result<usize>
+where
+    R: BufRead,
+    F: FnMut(&[&str]) -> Result<T, String>,
+{
+    let mut items = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = if i == 0 {
+            line.trim_start_matches('\u{FEFF}')
+        } else {
+            &line
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let invalid = |message: String| Error::malformed(i + 1, message);
+        let fields = format.split(line).map_err(invalid)?;
+        let fields: Vec<&str> = fields.iter().map(|f| f.as_ref()).collect();
+        let (word, rest) = match fields.split_first() {
+            Some((word, rest)) if !word.is_empty() => (*word, rest),
+            _ => return Err(invalid("empty word".to_string())),
+        };
+        let value = parser(rest).map_err(invalid)?;
+        items.push((word.to_string(), value));
+    }
+
+    let n = items.len();
+    trie.extend(items);
+    Ok(n)
+}
+
+/// 从文件读取词典，格式错误中带有文件路径，参见 [`read_into`]
+pub fn load_file<T, P, F>(path: P, format: Format, parser: F) -> error::Result<Trie<T>>
+where
+    P: AsRef<Path>,
+    F: FnMut(&[&str]) -> Result<T, String>,
+{
+    let mut trie = Trie::default();
+    let path = path.as_ref();
+    read_into(&mut trie, BufReader::new(File::open(path)?), format, parser)
+        .map_err(|e| e.in_file(path))?;
+    Ok(trie)
+}
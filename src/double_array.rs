@@ -0,0 +1,352 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::str::CharIndices;
+
+use crate::normalize::Normalize;
+use crate::tokenizer::{PrefixAllTokenizer, PrefixMaxTokenizer, PrefixSearch, Token};
+use crate::{Dictionary, Status, Trie};
+
+/// check 数组中空闲位置的标记
+const FREE: u32 = u32::MAX;
+const NO_VALUE: u32 = u32::MAX;
+
+/// 双数组 Trie 树，由 [`Trie`] 构建，构建后只读。
+/// 状态 s 经过字符编码 code 转移到 `base[s] + code`，当且仅当 `check[base[s] + code] == s`，
+/// 每次转移只需要一次字符编码查找和一次数组访问
+///
+/// 字符按在词典中出现的次数编码，出现越多编码越小，数组越紧凑
+/// # Examples
+/// ```rust
+/// use char_trie::{DoubleArrayTrie, Trie};
+/// let trie: Trie<_> = [("中国", "cn"), ("中国人", "cns"), ("国人", "gr")].into_iter().collect();
+/// let dat = DoubleArrayTrie::from(trie);
+/// assert_eq!(dat.get("中国人"), Some(&"cns"));
+/// assert_eq!(dat.get("中"), None);
+/// let tokens: Vec<_> = dat.iter_all("我是中国人").map(|t| t.0).collect();
+/// assert_eq!(tokens, vec!["中国", "中国人", "国人"]);
+/// let tokens: Vec<_> = dat.iter_max("我是中国人").map(|t| t.0).collect();
+/// assert_eq!(tokens, vec!["中国人"]);
+/// ```
+#[derive(Debug)]
+pub struct DoubleArrayTrie<T> {
+    /// 没有子节点的状态 base 为 0
+    base: Vec<u32>,
+    check: Vec<u32>,
+    /// 状态对应的值在 values 中的下标，不是词时为 NO_VALUE
+    value: Vec<u32>,
+    values: Vec<T>,
+    /// 字符编码，从 1 开始
+    alphabet: HashMap<char, u32>,
+    normalize: Normalize,
+}
+
+impl<T> DoubleArrayTrie<T> {
+    /// 字符归一化选项，与构建时的 Trie 树相同
+    pub fn normalize(&self) -> Normalize {
+        self.normalize
+    }
+
+    /// 词典中词的数量
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn transition(&self, state: usize, c: char) -> Option<usize> {
+        let code = *self.alphabet.get(&self.normalize.apply(c))?;
+        let next = self.base[state] as usize + code as usize;
+        (self.base[state] != 0 && self.check.get(next) == Some(&(state as u32))).then_some(next)
+    }
+
+    fn value(&self, state: usize) -> Option<&T> {
+        match self.value[state] {
+            NO_VALUE => None,
+            index => self.values.get(index as usize),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&T> {
+        if key.is_empty() {
+            return None;
+        }
+        let state = key
+            .chars()
+            .try_fold(0, |state, c| self.transition(state, c))?;
+        self.value(state)
+    }
+
+    /// 公共前缀查询，参见 [`Trie::common_prefixes`]
+    pub fn common_prefixes<'a>(&'a self, text: &'a str) -> DoubleArrayPrefixes<'a, T> {
+        DoubleArrayPrefixes {
+            trie: self,
+            state: 0,
+            chars: text.char_indices(),
+        }
+    }
+
+    /// 正向最大匹配，结果与 [`Trie::iter_max`] 相同
+    pub fn iter_max<'a>(&'a self, text: &'a str) -> PrefixMaxTokenizer<'a, &'a Self> {
+        PrefixMaxTokenizer::new(self, text)
+    }
+
+    /// 全词匹配，结果与 [`Trie::iter_all`] 相同
+    pub fn iter_all<'a>(&'a self, text: &'a str) -> PrefixAllTokenizer<'a, &'a Self> {
+        PrefixAllTokenizer::new(self, text)
+    }
+}
+
+impl<'a, T> PrefixSearch<'a> for &'a DoubleArrayTrie<T> {
+    type Value = T;
+    type Prefixes = DoubleArrayPrefixes<'a, T>;
+
+    fn common_prefixes(self, text: &'a str) -> DoubleArrayPrefixes<'a, T> {
+        DoubleArrayTrie::common_prefixes(self, text)
+    }
+}
+
+impl<T> Dictionary for DoubleArrayTrie<T> {
+    type Value = T;
+
+    fn get(&self, key: &str) -> Option<&T> {
+        DoubleArrayTrie::get(self, key)
+    }
+
+    fn iter_max<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Token<'a, T>> {
+        DoubleArrayTrie::iter_max(self, text)
+    }
+
+    fn iter_all<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Token<'a, T>> {
+        DoubleArrayTrie::iter_all(self, text)
+    }
+}
+
+/// [`DoubleArrayTrie`] 的公共前缀查询，结果为 (词在文本中的字节长度, 值)
+pub struct DoubleArrayPrefixes<'a, T> {
+    trie: &'a DoubleArrayTrie<T>,
+    state: usize,
+    chars: CharIndices<'a>,
+}
+
+impl<'a, T> Iterator for DoubleArrayPrefixes<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((i, c)) = self.chars.next() {
+            let Some(state) = self.trie.transition(self.state, c) else {
+                break;
+            };
+            self.state = state;
+            if self.trie.base[state] == 0 {
+                self.chars = "".char_indices();
+            }
+            if let Some(value) = self.trie.value(state) {
+                return Some((i + c.len_utf8(), value));
+            }
+        }
+        self.chars = "".char_indices();
+        None
+    }
+}
+
+/// 空闲位置组成的循环双向链表，位置 0 是根节点，永远被占用，用作链表头
+struct FreeList {
+    next: Vec<u32>,
+    prev: Vec<u32>,
+}
+
+/// 不在链表中的位置的标记
+const UNLINKED: u32 = u32::MAX;
+
+impl FreeList {
+    fn new() -> Self {
+        Self {
+            next: vec![0],
+            prev: vec![0],
+        }
+    }
+
+    /// 在链表末尾加入新扩展的位置 pos，pos 等于当前数组的长度
+    fn push(&mut self, pos: usize) {
+        let last = self.prev[0];
+        self.next.push(0);
+        self.prev.push(last);
+        self.next[last as usize] = pos as u32;
+        self.prev[0] = pos as u32;
+    }
+
+    fn next(&self, pos: usize) -> usize {
+        self.next[pos] as usize
+    }
+
+    fn remove(&mut self, pos: usize) {
+        let (prev, next) = (self.prev[pos], self.next[pos]);
+        if next == UNLINKED {
+            return;
+        }
+        self.next[prev as usize] = next;
+        self.prev[next as usize] = prev;
+        self.next[pos] = UNLINKED;
+        self.prev[pos] = UNLINKED;
+    }
+}
+
+/// 空闲位置作为多个子节点的候选时最多失败的次数，之后不再作为这类候选，
+/// 避免每次都重复扫描难以利用的空位，只有一个子节点时仍然可以使用
+const MAX_FAILS: u8 = 16;
+
+/// 构建时使用的数组，按需扩展
+struct Builder {
+    base: Vec<u32>,
+    check: Vec<u32>,
+    value: Vec<u32>,
+    /// 所有空闲位置
+    free: FreeList,
+    /// 还可以作为多个子节点候选的空闲位置
+    candidates: FreeList,
+    fails: Vec<u8>,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            base: vec![0],
+            check: vec![FREE],
+            value: vec![NO_VALUE],
+            free: FreeList::new(),
+            candidates: FreeList::new(),
+            fails: vec![0],
+        }
+    }
+
+    fn reserve(&mut self, len: usize) {
+        for pos in self.check.len()..len {
+            self.base.push(0);
+            self.check.push(FREE);
+            self.value.push(NO_VALUE);
+            self.fails.push(0);
+            self.free.push(pos);
+            self.candidates.push(pos);
+        }
+    }
+
+    /// 沿空闲链表找到一个 base，使所有 base + code 的位置都空闲。
+    /// 候选位置对应最大的编码，其余子节点都落在它前面，不需要扩展数组
+    fn find_base(&mut self, codes: &[usize]) -> usize {
+        let max = codes.iter().copied().max().unwrap_or(1);
+        let single = codes.len() == 1;
+        let mut pos = self.list(single).next(0);
+        loop {
+            if pos == 0 {
+                // 链表已经走完，在末尾扩展
+                pos = self.check.len().max(max + 1);
+                self.reserve(pos + 1);
+            }
+            if pos > max {
+                let base = pos - max;
+                if codes.iter().all(|&code| self.check[base + code] == FREE) {
+                    return base;
+                }
+                if !single {
+                    self.fails[pos] += 1;
+                }
+            }
+            let next = self.list(single).next(pos);
+            if self.fails[pos] == MAX_FAILS {
+                self.candidates.remove(pos);
+            }
+            pos = next;
+        }
+    }
+
+    /// 只有一个子节点时任意空闲位置都可以使用
+    fn list(&self, single: bool) -> &FreeList {
+        if single {
+            &self.free
+        } else {
+            &self.candidates
+        }
+    }
+
+    fn occupy(&mut self, pos: usize, parent: usize) {
+        self.check[pos] = parent as u32;
+        self.free.remove(pos);
+        self.candidates.remove(pos);
+    }
+}
+
+/// 由 Trie 树构建，保留 Trie 树的归一化选项
+impl<T> From<Trie<T>> for DoubleArrayTrie<T> {
+    fn from(trie: Trie<T>) -> Self {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        let mut stack = vec![&trie];
+        while let Some(node) = stack.pop() {
+            for child in node.leafs.iter() {
+                *counts.entry(child.c).or_default() += 1;
+                stack.push(child);
+            }
+        }
+        let mut chars: Vec<_> = counts.into_iter().collect();
+        chars.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        let alphabet: HashMap<char, u32> = chars
+            .into_iter()
+            .enumerate()
+            .map(|(i, (c, _))| (c, i as u32 + 1))
+            .collect();
+
+        let normalize = trie.normalize;
+        let mut builder = Builder::new();
+        let mut values = Vec::new();
+        let mut queue = VecDeque::from([(trie, 0)]);
+        while let Some((mut node, state)) = queue.pop_front() {
+            if node.status != Status::Not {
+                if let Some(value) = node.value.take() {
+                    builder.value[state] = values.len() as u32;
+                    values.push(value);
+                }
+            }
+
+            let children: Vec<Trie<T>> = mem::take(&mut node.leafs).into_iter().collect();
+            if children.is_empty() {
+                continue;
+            }
+            let codes: Vec<usize> = children.iter().map(|c| alphabet[&c.c] as usize).collect();
+            let base = builder.find_base(&codes);
+            builder.base[state] = base as u32;
+            for (child, code) in children.into_iter().zip(codes) {
+                builder.occupy(base + code, state);
+                queue.push_back((child, base + code));
+            }
+        }
+
+        // 末尾的空闲位置不会被访问到，转移时会做越界检查
+        let len = builder.check.iter().rposition(|&c| c != FREE).unwrap_or(0) + 1;
+        let Builder {
+            mut base,
+            mut check,
+            mut value,
+            ..
+        } = builder;
+        for v in [&mut base, &mut check, &mut value] {
+            v.truncate(len);
+            v.shrink_to_fit();
+        }
+
+        Self {
+            base,
+            check,
+            value,
+            values,
+            alphabet,
+            normalize,
+        }
+    }
+}
+
+impl<K: AsRef<str>, T> FromIterator<(K, T)> for DoubleArrayTrie<T> {
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        iter.into_iter().collect::<Trie<T>>().into()
+    }
+}
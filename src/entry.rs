@@ -0,0 +1,82 @@
+use crate::Trie;
+
+/// Trie 中一个词的入口，由 [`Trie::entry`] 创建
+pub enum Entry<'a, T> {
+    Occupied(OccupiedEntry<'a, T>),
+    Vacant(VacantEntry<'a, T>),
+}
+
+/// 已经存在的词
+pub struct OccupiedEntry<'a, T> {
+    pub(crate) trie: &'a mut Trie<T>,
+}
+
+/// 尚不存在的词
+pub struct VacantEntry<'a, T> {
+    pub(crate) root: &'a mut Trie<T>,
+    pub(crate) key: &'a str,
+}
+
+impl<'a, T> Entry<'a, T> {
+    /// 词不存在时插入默认值，返回值的可变引用
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// 词不存在时插入函数返回的值，返回值的可变引用
+    pub fn or_insert_with<F: FnOnce() -> T>(self, default: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// 词已存在时原地修改它的值
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, T: Default> Entry<'a, T> {
+    /// 词不存在时插入 `T::default()`，返回值的可变引用
+    pub fn or_default(self) -> &'a mut T {
+        self.or_insert_with(T::default)
+    }
+}
+
+impl<'a, T> OccupiedEntry<'a, T> {
+    pub fn get(&self) -> &T {
+        self.trie.value.as_ref().unwrap()
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.trie.value.as_mut().unwrap()
+    }
+
+    pub fn into_mut(self) -> &'a mut T {
+        self.trie.value.as_mut().unwrap()
+    }
+
+    /// 替换词的值，返回旧值
+    pub fn insert(&mut self, value: T) -> T {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a, T> VacantEntry<'a, T> {
+    pub fn key(&self) -> &'a str {
+        self.key
+    }
+
+    /// 插入词，返回值的可变引用
+    pub fn insert(self, value: T) -> &'a mut T {
+        let (trie, _) = self.root.inner_insert(self.key.chars(), value);
+        trie.value.as_mut().unwrap()
+    }
+}
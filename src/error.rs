@@ -0,0 +1,95 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// 词典加载、插入和二进制镜像读取的错误
+/// # Examples
+/// ```rust
+/// use char_trie::dict::{self, Format};
+/// use char_trie::{Error, Trie};
+/// let mut trie = Trie::default();
+/// let err = dict::read_into(&mut trie, "中国\n中国人\tn\tx\n".as_bytes(), Format::Ansj, dict::ansj_value)
+///     .unwrap_err();
+/// assert!(matches!(err, Error::Malformed { line: 1, .. }));
+/// assert!(trie.is_empty());
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// 词典文件中格式错误的行，line 从 1 开始，从内存读取时 file 为 None
+    Malformed {
+        file: Option<PathBuf>,
+        line: usize,
+        message: String,
+    },
+    /// 插入空字符串作为词
+    EmptyKey,
+    /// 二进制镜像损坏，或者不是本库写出的格式
+    Corrupted(String),
+    /// 写出镜像时节点数、词数或值数据的字节数超过 u32 的范围
+    TooLarge,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn malformed(line: usize, message: impl Into<String>) -> Self {
+        Error::Malformed {
+            file: None,
+            line,
+            message: message.into(),
+        }
+    }
+
+    /// 为格式错误补充文件路径
+    pub(crate) fn in_file(self, path: impl Into<PathBuf>) -> Self {
+        match self {
+            Error::Malformed {
+                file: None,
+                line,
+                message,
+            } => Error::Malformed {
+                file: Some(path.into()),
+                line,
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "io error: {}", e),
+            Error::Malformed {
+                file: Some(file),
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", file.display(), line, message),
+            Error::Malformed {
+                file: None,
+                line,
+                message,
+            } => write!(f, "line {}: {}", line, message),
+            Error::EmptyKey => write!(f, "empty key"),
+            Error::Corrupted(message) => write!(f, "corrupted image: {}", message),
+            Error::TooLarge => write!(f, "trie is too large for the image format"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
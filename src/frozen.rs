@@ -0,0 +1,310 @@
+//! 只读的 Trie 树，直接在 [`Trie::write_to`](crate::Trie::write_to) 写出的镜像上查询，不需要反序列化。
+//! 镜像可以来自内存映射的文件，多个进程共享同一份物理内存
+//!
+//! [`FrozenTrie`] 的值以编码后的字节返回，需要时用 [`Codec::decode`] 解码。
+//! [`TypedFrozenTrie`] 打开时解码所有的值，返回 `&T`，可以直接替换 [`Trie`](crate::Trie)
+//!
+//! ```rust
+//! use char_trie::frozen::FrozenTrie;
+//! use char_trie::image::Codec;
+//! use char_trie::Trie;
+//! let trie: Trie<_> = [("中国", 1u32), ("中国人", 2), ("国人", 3)].into_iter().collect();
+//! let mut image = Vec::new();
+//! trie.write_to(&mut image).unwrap();
+//!
+//! let frozen = FrozenTrie::new(&image).unwrap();
+//! let mut value = frozen.get("中国人").unwrap();
+//! assert_eq!(u32::decode(&mut value), Some(2));
+//! let text = "我是中国人";
+//! let tokens: Vec<_> = frozen.iter_all(text).map(|t| t.1).collect();
+//! assert_eq!(tokens, trie.iter_all(text).map(|t| t.1).collect::<Vec<_>>());
+//! ```
+//!
+//! [`Codec::decode`]: crate::image::Codec::decode
+
+use std::str::CharIndices;
+
+use crate::error::{self, Error};
+use crate::image::{Codec, Image, RawNode, NO_VALUE};
+use crate::normalize::Normalize;
+use crate::tokenizer::{PrefixAllTokenizer, PrefixMaxTokenizer, PrefixSearch, Token};
+use crate::Dictionary;
+
+/// 镜像上的只读 Trie 树，打开时校验一次镜像，之后的查询不再复制数据
+#[derive(Debug, Clone, Copy)]
+pub struct FrozenTrie<'a> {
+    image: Image<'a>,
+}
+
+impl<'a> FrozenTrie<'a> {
+    /// 打开镜像，校验头部、校验和以及节点结构，镜像不合法时返回 [`crate::Error::Corrupted`]
+    pub fn new(bytes: &'a [u8]) -> error::Result<Self> {
+        Ok(Self {
+            image: Image::parse(bytes)?,
+        })
+    }
+
+    /// 字符归一化选项，与写出镜像的 Trie 树相同
+    pub fn normalize(&self) -> Normalize {
+        self.image.normalize
+    }
+
+    /// 词典中词的数量
+    pub fn len(&self) -> usize {
+        self.image.value_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 查询词编码后的值
+    pub fn get(&self, key: &str) -> Option<&'a [u8]> {
+        self.value_index(key)
+            .map(|index| self.image.value_bytes(index))
+    }
+
+    /// 词的值在镜像中的下标
+    fn value_index(&self, key: &str) -> Option<u32> {
+        if key.is_empty() {
+            return None;
+        }
+        let mut node = self.image.node(0);
+        for c in key.chars() {
+            node = self.image.child(&node, self.image.normalize.apply(c))?;
+        }
+        (node.value != NO_VALUE).then_some(node.value)
+    }
+
+    /// 公共前缀查询，参见 [`crate::Trie::common_prefixes`]
+    pub fn common_prefixes<'b>(&'b self, text: &'b str) -> FrozenPrefixes<'b> {
+        FrozenPrefixes {
+            indices: self.prefix_indices(text),
+        }
+    }
+
+    fn prefix_indices<'b>(&self, text: &'b str) -> PrefixIndices<'b>
+    where
+        'a: 'b,
+    {
+        PrefixIndices {
+            image: self.image,
+            node: self.image.node(0),
+            chars: text.char_indices(),
+        }
+    }
+
+    /// 正向最大匹配，结果与 [`crate::Trie::iter_max`] 相同
+    pub fn iter_max<'b>(&'b self, text: &'b str) -> PrefixMaxTokenizer<'b, &'b Self> {
+        PrefixMaxTokenizer::new(self, text)
+    }
+
+    /// 全词匹配，结果与 [`crate::Trie::iter_all`] 相同
+    pub fn iter_all<'b>(&'b self, text: &'b str) -> PrefixAllTokenizer<'b, &'b Self> {
+        PrefixAllTokenizer::new(self, text)
+    }
+}
+
+impl<'b> PrefixSearch<'b> for &'b FrozenTrie<'_> {
+    type Value = [u8];
+    type Prefixes = FrozenPrefixes<'b>;
+
+    fn common_prefixes(self, text: &'b str) -> FrozenPrefixes<'b> {
+        FrozenTrie::common_prefixes(self, text)
+    }
+}
+
+impl Dictionary for FrozenTrie<'_> {
+    type Value = [u8];
+
+    fn get(&self, key: &str) -> Option<&[u8]> {
+        FrozenTrie::get(self, key)
+    }
+
+    fn iter_max<'b>(&'b self, text: &'b str) -> impl Iterator<Item = Token<'b, [u8]>> {
+        FrozenTrie::iter_max(self, text)
+    }
+
+    fn iter_all<'b>(&'b self, text: &'b str) -> impl Iterator<Item = Token<'b, [u8]>> {
+        FrozenTrie::iter_all(self, text)
+    }
+}
+
+/// [`FrozenTrie`] 的公共前缀查询，结果为 (词在文本中的字节长度, 编码后的值)
+pub struct FrozenPrefixes<'a> {
+    indices: PrefixIndices<'a>,
+}
+
+impl<'a> Iterator for FrozenPrefixes<'a> {
+    type Item = (usize, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (len, index) = self.indices.next()?;
+        Some((len, self.indices.image.value_bytes(index)))
+    }
+}
+
+/// 在镜像上做公共前缀查询，结果为 (词在文本中的字节长度, 值的下标)
+struct PrefixIndices<'a> {
+    image: Image<'a>,
+    node: RawNode,
+    chars: CharIndices<'a>,
+}
+
+impl Iterator for PrefixIndices<'_> {
+    type Item = (usize, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((i, c)) = self.chars.next() {
+            let Some(node) = self.image.child(&self.node, self.image.normalize.apply(c)) else {
+                break;
+            };
+            self.node = node;
+            if node.child_count == 0 {
+                self.chars = "".char_indices();
+            }
+            if node.value != NO_VALUE {
+                return Some((i + c.len_utf8(), node.value));
+            }
+        }
+        self.chars = "".char_indices();
+        None
+    }
+}
+
+/// 值解码后的 [`FrozenTrie`]，查询结果为 `&T`，与 [`Trie`](crate::Trie) 和
+/// [`DoubleArrayTrie`](crate::DoubleArrayTrie) 的输出相同，可以通过 [`Dictionary`] 直接替换。
+/// 打开时一次解码所有的值，节点表仍然直接在镜像上查询
+/// # Examples
+/// ```rust
+/// use char_trie::frozen::TypedFrozenTrie;
+/// use char_trie::{Dictionary, Trie};
+/// fn tokens<D: Dictionary<Value = u32>>(dict: &D, text: &str) -> Vec<(String, u32)> {
+///     dict.iter_all(text).map(|t| (t.0.to_string(), *t.2.unwrap())).collect()
+/// }
+/// let trie: Trie<_> = [("中国", 1u32), ("中国人", 2), ("国人", 3)].into_iter().collect();
+/// let mut image = Vec::new();
+/// trie.write_to(&mut image).unwrap();
+///
+/// let frozen: TypedFrozenTrie<u32> = TypedFrozenTrie::new(&image).unwrap();
+/// assert_eq!(frozen.get("中国人"), Some(&2));
+/// assert_eq!(tokens(&frozen, "我是中国人"), tokens(&trie, "我是中国人"));
+/// ```
+#[derive(Debug)]
+pub struct TypedFrozenTrie<'a, T> {
+    trie: FrozenTrie<'a>,
+    values: Vec<T>,
+}
+
+impl<'a, T: Codec> TypedFrozenTrie<'a, T> {
+    /// 打开镜像并解码所有的值，镜像不合法或者值无法解码为 T 时返回 [`Error::Corrupted`]
+    pub fn new(bytes: &'a [u8]) -> error::Result<Self> {
+        let trie = FrozenTrie::new(bytes)?;
+        let values = (0..trie.len() as u32)
+            .map(|index| {
+                let mut input = trie.image.value_bytes(index);
+                match T::decode(&mut input) {
+                    Some(value) if input.is_empty() => Ok(value),
+                    _ => Err(Error::Corrupted(format!("invalid value {}", index))),
+                }
+            })
+            .collect::<error::Result<_>>()?;
+        Ok(Self { trie, values })
+    }
+}
+
+impl<'a, T> TypedFrozenTrie<'a, T> {
+    /// 字符归一化选项，与写出镜像的 Trie 树相同
+    pub fn normalize(&self) -> Normalize {
+        self.trie.normalize()
+    }
+
+    /// 词典中词的数量
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&T> {
+        self.trie
+            .value_index(key)
+            .map(|index| &self.values[index as usize])
+    }
+
+    /// 公共前缀查询，参见 [`crate::Trie::common_prefixes`]
+    pub fn common_prefixes<'b>(&'b self, text: &'b str) -> TypedFrozenPrefixes<'b, T> {
+        TypedFrozenPrefixes {
+            indices: self.trie.prefix_indices(text),
+            values: &self.values,
+        }
+    }
+
+    /// 正向最大匹配，结果与 [`crate::Trie::iter_max`] 相同
+    pub fn iter_max<'b>(&'b self, text: &'b str) -> PrefixMaxTokenizer<'b, &'b Self> {
+        PrefixMaxTokenizer::new(self, text)
+    }
+
+    /// 全词匹配，结果与 [`crate::Trie::iter_all`] 相同
+    pub fn iter_all<'b>(&'b self, text: &'b str) -> PrefixAllTokenizer<'b, &'b Self> {
+        PrefixAllTokenizer::new(self, text)
+    }
+}
+
+impl<'b, T> PrefixSearch<'b> for &'b TypedFrozenTrie<'_, T> {
+    type Value = T;
+    type Prefixes = TypedFrozenPrefixes<'b, T>;
+
+    fn common_prefixes(self, text: &'b str) -> TypedFrozenPrefixes<'b, T> {
+        TypedFrozenTrie::common_prefixes(self, text)
+    }
+}
+
+impl<T> Dictionary for TypedFrozenTrie<'_, T> {
+    type Value = T;
+
+    fn get(&self, key: &str) -> Option<&T> {
+        TypedFrozenTrie::get(self, key)
+    }
+
+    fn iter_max<'b>(&'b self, text: &'b str) -> impl Iterator<Item = Token<'b, T>> {
+        TypedFrozenTrie::iter_max(self, text)
+    }
+
+    fn iter_all<'b>(&'b self, text: &'b str) -> impl Iterator<Item = Token<'b, T>> {
+        TypedFrozenTrie::iter_all(self, text)
+    }
+}
+
+/// [`TypedFrozenTrie`] 的公共前缀查询，结果为 (词在文本中的字节长度, 值)
+pub struct TypedFrozenPrefixes<'a, T> {
+    indices: PrefixIndices<'a>,
+    values: &'a [T],
+}
+
+impl<'a, T> Iterator for TypedFrozenPrefixes<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (len, index) = self.indices.next()?;
+        Some((len, &self.values[index as usize]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::tests::{damage, node, sample_image};
+
+    #[test]
+    fn rejects_invalid_structure() {
+        assert!(FrozenTrie::new(&sample_image()).is_ok());
+        // 节点 “中” 的子节点指向自己
+        let image = damage(&sample_image(), node(1) + 4, 1);
+        let err = FrozenTrie::new(&image).unwrap_err();
+        assert!(matches!(err, Error::Corrupted(message) if message.contains("invalid children")));
+        assert!(TypedFrozenTrie::<u32>::new(&image).is_err());
+    }
+}
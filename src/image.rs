@@ -0,0 +1,610 @@
+//! Trie 树的二进制镜像，预先构建好的词典可以直接加载，不需要重新解析文本和插入
+//!
+//! 镜像为小端序，由四部分组成：
+//! 1. 头部 24 字节：魔数 `CTRI`、版本号 u16、标志 u16（低 8 位为归一化选项，第 8 位表示根节点为 Big 结构）、
+//!    节点数 u32、值的个数 u32、值数据的字节数 u32、除校验和字段以外所有数据的 CRC32
+//! 2. 节点表：按广度优先顺序排列，每个节点 16 字节：字符 u32、第一个子节点的下标 u32、子节点数 u32、
+//!    值的下标 u32（不是词时为 u32::MAX）。下标 0 为根节点，同一节点的子节点连续存放并按字符排序
+//! 3. 值的偏移表：值的个数 + 1 个 u32
+//! 4. 值数据：每个值由 [`Codec`] 编码
+//!
+//! ```rust
+//! use char_trie::Trie;
+//! let trie: Trie<_> = [("中国", 1u32), ("中国人", 2)].into_iter().collect();
+//! let mut image = Vec::new();
+//! trie.write_to(&mut image).unwrap();
+//! let loaded: Trie<u32> = Trie::read_from(image.as_slice()).unwrap();
+//! assert_eq!(loaded.get("中国人"), Some(&2));
+//! assert_eq!(loaded.len(), 2);
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+
+use crate::error::{self, Error};
+use crate::normalize::Normalize;
+use crate::{BigLeafs, Leafs, Status, Trie};
+
+const MAGIC: &[u8; 4] = b"CTRI";
+const VERSION: u16 = 1;
+pub(crate) const HEADER_LEN: usize = 24;
+/// 校验和在头部的位置，位于头部末尾
+const CRC_POS: usize = HEADER_LEN - 4;
+pub(crate) const NODE_LEN: usize = 16;
+pub(crate) const NO_VALUE: u32 = u32::MAX;
+/// 标志中表示根节点为 Big 结构的位
+const FLAG_BIG: u16 = 1 << 8;
+
+/// 值的二进制编码，用于读写镜像
+/// # Examples
+/// ```rust
+/// use char_trie::image::Codec;
+/// let mut buf = Vec::new();
+/// (String::from("ns"), 100i32).encode(&mut buf);
+/// let mut input = buf.as_slice();
+/// assert_eq!(<(String, i32)>::decode(&mut input), Some((String::from("ns"), 100)));
+/// assert!(input.is_empty());
+/// ```
+pub trait Codec: Sized {
+    /// 把值追加到 out 末尾
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// 从 input 开头读取一个值，并把 input 前移到值之后，数据不合法时返回 None
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if input.len() < n {
+        return None;
+    }
+    let (bytes, rest) = input.split_at(n);
+    *input = rest;
+    Some(bytes)
+}
+
+macro_rules! impl_codec_number {
+    ($($t:ty),*) => {$(
+        impl Codec for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn decode(input: &mut &[u8]) -> Option<Self> {
+                let (bytes, rest) = input.split_first_chunk()?;
+                *input = rest;
+                Some(<$t>::from_le_bytes(*bytes))
+            }
+        }
+    )*};
+}
+
+impl_codec_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+/// 按 u64 编码，保证镜像与平台无关
+impl Codec for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        usize::try_from(u64::decode(input)?).ok()
+    }
+}
+
+/// 按 i64 编码，保证镜像与平台无关
+impl Codec for isize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as i64).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        isize::try_from(i64::decode(input)?).ok()
+    }
+}
+
+impl Codec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Codec for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u32).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        char::from_u32(u32::decode(input)?)
+    }
+}
+
+impl Codec for () {
+    fn encode(&self, _: &mut Vec<u8>) {}
+
+    fn decode(_: &mut &[u8]) -> Option<Self> {
+        Some(())
+    }
+}
+
+/// u32 长度 + UTF-8 字节
+impl Codec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = u32::decode(input)? as usize;
+        let bytes = take(input, len)?;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+/// u32 长度 + 各个元素
+impl<T: Codec> Codec for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = u32::decode(input)? as usize;
+        // 长度来自输入，不可信，预分配不超过剩余的字节数
+        let mut items = Vec::with_capacity(len.min(input.len()));
+        for _ in 0..len {
+            items.push(T::decode(input)?);
+        }
+        Some(items)
+    }
+}
+
+/// 1 字节标记 + 值
+impl<T: Codec> Codec for Option<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Some(v) => {
+                out.push(1);
+                v.encode(out);
+            }
+            None => out.push(0),
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(None),
+            1 => Some(Some(T::decode(input)?)),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! impl_codec_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: Codec),+> Codec for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn encode(&self, out: &mut Vec<u8>) {
+                let ($($name,)+) = self;
+                $($name.encode(out);)+
+            }
+
+            fn decode(input: &mut &[u8]) -> Option<Self> {
+                Some(($($name::decode(input)?,)+))
+            }
+        }
+    };
+}
+
+impl_codec_tuple!(A);
+impl_codec_tuple!(A B);
+impl_codec_tuple!(A B C);
+impl_codec_tuple!(A B C D);
+
+/// CRC-32 (IEEE) 查找表
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// 依次计算多段数据的 CRC32
+fn crc32(parts: &[&[u8]]) -> u32 {
+    !parts
+        .iter()
+        .flat_map(|part| part.iter())
+        .fold(!0u32, |crc, &b| {
+            CRC_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+        })
+}
+
+fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+}
+
+fn to_u32(n: usize) -> error::Result<u32> {
+    u32::try_from(n).map_err(|_| Error::TooLarge)
+}
+
+fn corrupted(message: impl Into<String>) -> Error {
+    Error::Corrupted(message.into())
+}
+
+/// 镜像中的一个节点
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RawNode {
+    pub(crate) c: char,
+    pub(crate) first_child: u32,
+    pub(crate) child_count: u32,
+    pub(crate) value: u32,
+}
+
+impl RawNode {
+    pub(crate) fn children(&self) -> std::ops::Range<u32> {
+        self.first_child..self.first_child + self.child_count
+    }
+}
+
+/// 校验过的镜像，各部分的位置都已确定，节点结构保证是一棵树
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Image<'a> {
+    pub(crate) normalize: Normalize,
+    pub(crate) big: bool,
+    nodes: &'a [u8],
+    offsets: &'a [u8],
+    values: &'a [u8],
+}
+
+impl<'a> Image<'a> {
+    pub(crate) fn parse(bytes: &'a [u8]) -> error::Result<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err(corrupted("not a char_trie image"));
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != VERSION {
+            return Err(corrupted(format!("unsupported version {}", version)));
+        }
+        let flags = u16::from_le_bytes([bytes[6], bytes[7]]);
+        if flags & !(FLAG_BIG | 0xFF) != 0 {
+            return Err(corrupted(format!("unknown flags {:#x}", flags)));
+        }
+        let normalize = Normalize::from_bits(flags as u8)
+            .ok_or_else(|| corrupted(format!("unknown normalize flags {:#x}", flags as u8)))?;
+        let node_count = read_u32(bytes, 8) as usize;
+        let value_count = read_u32(bytes, 12) as usize;
+        let values_len = read_u32(bytes, 16) as usize;
+        let crc = read_u32(bytes, CRC_POS);
+
+        let nodes_end = HEADER_LEN as u64 + node_count as u64 * NODE_LEN as u64;
+        let offsets_end = nodes_end + (value_count as u64 + 1) * 4;
+        if node_count == 0 || offsets_end + values_len as u64 != bytes.len() as u64 {
+            return Err(corrupted("length mismatch"));
+        }
+        if crc32(&[&bytes[..CRC_POS], &bytes[HEADER_LEN..]]) != crc {
+            return Err(corrupted("checksum mismatch"));
+        }
+
+        let (nodes_end, offsets_end) = (nodes_end as usize, offsets_end as usize);
+        let image = Image {
+            normalize,
+            big: flags & FLAG_BIG != 0,
+            nodes: &bytes[HEADER_LEN..nodes_end],
+            offsets: &bytes[nodes_end..offsets_end],
+            values: &bytes[offsets_end..],
+        };
+        image.validate(value_count, values_len)?;
+        Ok(image)
+    }
+
+    /// 检查节点按广度优先顺序排列、子节点按字符排序、下标不越界，
+    /// 之后的访问不需要再做检查
+    fn validate(&self, value_count: usize, values_len: usize) -> error::Result<()> {
+        let node_count = self.len();
+        let mut next = 1u64;
+        for i in 0..node_count {
+            let pos = i * NODE_LEN;
+            let c = read_u32(self.nodes, pos);
+            if char::from_u32(c).is_none() {
+                return Err(corrupted(format!("node {}: invalid char {:#x}", i, c)));
+            }
+            let first_child = read_u32(self.nodes, pos + 4) as u64;
+            let child_count = read_u32(self.nodes, pos + 8) as u64;
+            let value = read_u32(self.nodes, pos + 12);
+            if first_child != next || next + child_count > node_count as u64 {
+                return Err(corrupted(format!("node {}: invalid children", i)));
+            }
+            next += child_count;
+            if value != NO_VALUE && value as usize >= value_count {
+                return Err(corrupted(format!("node {}: invalid value index", i)));
+            }
+            if i == 0 && value != NO_VALUE {
+                return Err(corrupted("root node has a value"));
+            }
+            if i != 0 && child_count == 0 && value == NO_VALUE {
+                return Err(corrupted(format!("node {}: leaf without value", i)));
+            }
+            let children = self.node(i).children();
+            for (a, b) in children.clone().zip(children.skip(1)) {
+                if read_u32(self.nodes, a as usize * NODE_LEN)
+                    >= read_u32(self.nodes, b as usize * NODE_LEN)
+                {
+                    return Err(corrupted(format!("node {}: children not sorted", i)));
+                }
+            }
+        }
+        if next != node_count as u64 {
+            return Err(corrupted("unreachable nodes"));
+        }
+
+        let mut last = 0;
+        for i in 0..=value_count {
+            let offset = read_u32(self.offsets, i * 4) as usize;
+            if offset < last || (i == 0 && offset != 0) {
+                return Err(corrupted("invalid value offsets"));
+            }
+            last = offset;
+        }
+        if last != values_len {
+            return Err(corrupted("invalid value offsets"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len() / NODE_LEN
+    }
+
+    pub(crate) fn node(&self, index: usize) -> RawNode {
+        let pos = index * NODE_LEN;
+        RawNode {
+            c: char::from_u32(read_u32(self.nodes, pos)).unwrap_or(char::REPLACEMENT_CHARACTER),
+            first_child: read_u32(self.nodes, pos + 4),
+            child_count: read_u32(self.nodes, pos + 8),
+            value: read_u32(self.nodes, pos + 12),
+        }
+    }
+
+    /// 词的数量
+    pub(crate) fn value_count(&self) -> usize {
+        self.offsets.len() / 4 - 1
+    }
+
+    /// 在 node 的子节点中二分查找字符 c
+    pub(crate) fn child(&self, node: &RawNode, c: char) -> Option<RawNode> {
+        let (mut lo, mut hi) = (node.first_child as usize, node.children().end as usize);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let child = self.node(mid);
+            match child.c.cmp(&c) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return Some(child),
+            }
+        }
+        None
+    }
+
+    /// 下标为 index 的值编码后的字节
+    pub(crate) fn value_bytes(&self, index: u32) -> &'a [u8] {
+        let i = index as usize * 4;
+        let start = read_u32(self.offsets, i) as usize;
+        let end = read_u32(self.offsets, i + 4) as usize;
+        &self.values[start..end]
+    }
+}
+
+impl<T: Codec> Trie<T> {
+    /// 把 Trie 树写为二进制镜像，格式参见 [`crate::image`]。
+    /// 节点数、词数或值数据的字节数超过 u32 的范围时返回 [`Error::TooLarge`]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> error::Result<()> {
+        let mut nodes = Vec::new();
+        let mut offsets = vec![0u32];
+        let mut values = Vec::new();
+
+        let mut queue = VecDeque::from([self]);
+        let mut next = 1usize;
+        while let Some(node) = queue.pop_front() {
+            let first_child = next;
+            let queued = queue.len();
+            queue.extend(node.leafs.iter());
+            let child_count = queue.len() - queued;
+            next += child_count;
+
+            let value = match (&node.status, &node.value) {
+                (Status::End | Status::LastEnd, Some(value)) => {
+                    value.encode(&mut values);
+                    offsets.push(to_u32(values.len())?);
+                    to_u32(offsets.len() - 2)?
+                }
+                _ => NO_VALUE,
+            };
+            nodes.extend_from_slice(&(node.c as u32).to_le_bytes());
+            nodes.extend_from_slice(&to_u32(first_child)?.to_le_bytes());
+            nodes.extend_from_slice(&to_u32(child_count)?.to_le_bytes());
+            nodes.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut body = nodes;
+        for offset in &offsets {
+            body.extend_from_slice(&offset.to_le_bytes());
+        }
+        body.extend_from_slice(&values);
+
+        let mut flags = self.normalize.bits() as u16;
+        if matches!(self.leafs, Leafs::Big(_)) {
+            flags |= FLAG_BIG;
+        }
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&VERSION.to_le_bytes());
+        header.extend_from_slice(&flags.to_le_bytes());
+        header.extend_from_slice(&to_u32(next)?.to_le_bytes());
+        header.extend_from_slice(&to_u32(offsets.len() - 1)?.to_le_bytes());
+        header.extend_from_slice(&to_u32(values.len())?.to_le_bytes());
+        header.extend_from_slice(&crc32(&[&header, &body]).to_le_bytes());
+
+        writer.write_all(&header)?;
+        writer.write_all(&body)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// 读取 [`Trie::write_to`] 写出的镜像，版本不符、校验和错误或者数据不合法时返回 [`Error::Corrupted`]
+    /// # Examples
+    /// ```rust
+    /// use char_trie::normalize::Normalize;
+    /// use char_trie::{Error, Trie};
+    /// let mut trie = Trie::new_big().with_normalize(Normalize::ASCII_CASE);
+    /// trie.insert("Hello", (String::from("en"), 1));
+    /// let mut image = Vec::new();
+    /// trie.write_to(&mut image).unwrap();
+    ///
+    /// let loaded: Trie<(String, i32)> = Trie::read_from(image.as_slice()).unwrap();
+    /// assert_eq!(loaded.normalize(), Normalize::ASCII_CASE);
+    /// assert_eq!(loaded.get("HELLO"), Some(&(String::from("en"), 1)));
+    ///
+    /// let last = image.len() - 1;
+    /// image[last] ^= 1;
+    /// let err = Trie::<(String, i32)>::read_from(image.as_slice()).unwrap_err();
+    /// assert!(matches!(err, Error::Corrupted(_)));
+    /// ```
+    pub fn read_from<R: Read>(mut reader: R) -> error::Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let image = Image::parse(&bytes)?;
+
+        // 子节点的下标总是大于父节点，从后往前构建
+        let mut built: Vec<Option<Trie<T>>> = (0..image.len()).map(|_| None).collect();
+        for i in (0..image.len()).rev() {
+            let raw = image.node(i);
+            let value = if raw.value == NO_VALUE {
+                None
+            } else {
+                let mut input = image.value_bytes(raw.value);
+                match T::decode(&mut input) {
+                    Some(value) if input.is_empty() => Some(value),
+                    _ => return Err(corrupted(format!("invalid value {}", raw.value))),
+                }
+            };
+            let children: Vec<Trie<T>> = raw
+                .children()
+                .filter_map(|j| built[j as usize].take())
+                .collect();
+            let status = match (&value, children.is_empty()) {
+                (None, _) => Status::Not,
+                (Some(_), false) => Status::End,
+                (Some(_), true) => Status::LastEnd,
+            };
+            let leafs = if i == 0 && image.big {
+                let mut leafs = Leafs::Big(Box::new(BigLeafs::new()));
+                for child in children {
+                    if let Err(index) = leafs.binary_search(child.c) {
+                        leafs.insert(index, child);
+                    }
+                }
+                leafs
+            } else {
+                Leafs::Small(children)
+            };
+            built[i] = Some(Trie {
+                c: raw.c,
+                status,
+                normalize: image.normalize,
+                value,
+                leafs,
+            });
+        }
+        built[0].take().ok_or_else(|| corrupted("empty image"))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 节点按广度优先排列：0 根、1 中、2 国、3 中国、4 国人、5 中国人，之后是值的偏移表
+    pub(crate) fn sample_image() -> Vec<u8> {
+        let trie: Trie<_> = [("中国", 1u32), ("中国人", 2), ("国人", 3)]
+            .into_iter()
+            .collect();
+        let mut image = Vec::new();
+        trie.write_to(&mut image).unwrap();
+        image
+    }
+
+    /// 第 i 个节点在镜像中的位置
+    pub(crate) fn node(i: usize) -> usize {
+        HEADER_LEN + i * NODE_LEN
+    }
+
+    /// 修改 pos 处的 u32 后重新计算校验和，得到只有结构损坏的镜像
+    pub(crate) fn damage(image: &[u8], pos: usize, value: u32) -> Vec<u8> {
+        let mut image = image.to_vec();
+        image[pos..pos + 4].copy_from_slice(&value.to_le_bytes());
+        let crc = crc32(&[&image[..CRC_POS], &image[HEADER_LEN..]]);
+        image[CRC_POS..HEADER_LEN].copy_from_slice(&crc.to_le_bytes());
+        image
+    }
+
+    fn error(image: &[u8]) -> String {
+        match Trie::<u32>::read_from(image) {
+            Err(Error::Corrupted(message)) => message,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn damage_recomputes_checksum() {
+        let image = sample_image();
+        assert_eq!(damage(&image, node(1) + 4, 3), image);
+    }
+
+    #[test]
+    fn invalid_children() {
+        let image = damage(&sample_image(), node(0) + 8, 100);
+        assert!(error(&image).contains("invalid children"));
+    }
+
+    #[test]
+    fn children_not_sorted() {
+        // 根节点的子节点 “中” 改为 “国” 之后的字符
+        let image = damage(&sample_image(), node(1), '圆' as u32);
+        assert!(error(&image).contains("not sorted"));
+    }
+
+    #[test]
+    fn leaf_without_value() {
+        let image = damage(&sample_image(), node(4) + 12, NO_VALUE);
+        assert!(error(&image).contains("leaf without value"));
+    }
+
+    #[test]
+    fn invalid_value_offsets() {
+        let image = damage(&sample_image(), node(6) + 4, 1000);
+        assert!(error(&image).contains("invalid value offsets"));
+    }
+}
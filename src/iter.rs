@@ -0,0 +1,198 @@
+use crate::{LeafsIntoIter, LeafsIter, LeafsIterMut, Status, Trie};
+
+/// 按字符顺序深度优先遍历一棵子树，输出 (词, 值)
+pub struct Iter<'a, T> {
+    key: String,
+    stack: Vec<LeafsIter<'a, T>>,
+    pending: Option<&'a T>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    /// 从 trie 节点开始遍历，prefix 为该节点对应的词
+    pub(crate) fn new(trie: &'a Trie<T>, prefix: String) -> Self {
+        let pending = match trie.status {
+            Status::Not => None,
+            Status::End | Status::LastEnd => trie.value.as_ref(),
+        };
+        Self {
+            key: prefix,
+            stack: vec![trie.leafs.iter()],
+            pending,
+        }
+    }
+
+    pub(crate) fn empty() -> Self {
+        Self {
+            key: String::new(),
+            stack: Vec::new(),
+            pending: None,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = (String, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(value) = self.pending.take() {
+            return Some((self.key.clone(), value));
+        }
+
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(trie) => {
+                    self.key.push(trie.c);
+                    self.stack.push(trie.leafs.iter());
+                    if trie.status != Status::Not {
+                        if let Some(value) = trie.value.as_ref() {
+                            return Some((self.key.clone(), value));
+                        }
+                    }
+                }
+                None => {
+                    // 栈底是起始节点的子节点，不对应任何字符
+                    self.stack.pop();
+                    if !self.stack.is_empty() {
+                        self.key.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 按字符顺序输出所有的词
+pub struct Keys<'a, T> {
+    inner: Iter<'a, T>,
+}
+
+impl<'a, T> Keys<'a, T> {
+    pub(crate) fn new(inner: Iter<'a, T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T> Iterator for Keys<'_, T> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+}
+
+/// 按词的字符顺序输出所有的值，不需要拼接词
+pub struct Values<'a, T> {
+    stack: Vec<LeafsIter<'a, T>>,
+}
+
+impl<'a, T> Values<'a, T> {
+    pub(crate) fn new(trie: &'a Trie<T>) -> Self {
+        Self {
+            stack: vec![trie.leafs.iter()],
+        }
+    }
+}
+
+impl<'a, T> Iterator for Values<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(trie) => {
+                    self.stack.push(trie.leafs.iter());
+                    if trie.status != Status::Not {
+                        if let Some(value) = trie.value.as_ref() {
+                            return Some(value);
+                        }
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// 按词的字符顺序输出所有值的可变引用
+pub struct ValuesMut<'a, T> {
+    stack: Vec<LeafsIterMut<'a, T>>,
+}
+
+impl<'a, T> ValuesMut<'a, T> {
+    pub(crate) fn new(trie: &'a mut Trie<T>) -> Self {
+        Self {
+            stack: vec![trie.leafs.iter_mut()],
+        }
+    }
+}
+
+impl<'a, T> Iterator for ValuesMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(trie) => {
+                    let Trie {
+                        status,
+                        value,
+                        leafs,
+                        ..
+                    } = trie;
+                    self.stack.push(leafs.iter_mut());
+                    if *status != Status::Not {
+                        if let Some(value) = value.as_mut() {
+                            return Some(value);
+                        }
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// 按字符顺序消费整棵树，输出所有的 (词, 值)
+pub struct IntoIter<T> {
+    key: String,
+    stack: Vec<LeafsIntoIter<T>>,
+}
+
+impl<T> IntoIter<T> {
+    pub(crate) fn new(trie: Trie<T>) -> Self {
+        Self {
+            key: String::new(),
+            stack: vec![trie.leafs.into_iter()],
+        }
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (String, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.stack.last_mut()?.next() {
+                Some(trie) => {
+                    self.key.push(trie.c);
+                    self.stack.push(trie.leafs.into_iter());
+                    if trie.status != Status::Not {
+                        if let Some(value) = trie.value {
+                            return Some((self.key.clone(), value));
+                        }
+                    }
+                }
+                None => {
+                    self.stack.pop();
+                    if !self.stack.is_empty() {
+                        self.key.pop();
+                    }
+                }
+            }
+        }
+    }
+}
@@ -3,9 +3,9 @@
 //!
 //! Licensed under either of
 //! * Apache License, Version 2.0,
-//! (./LICENSE-APACHE or <http://www.apache.org/licenses/LICENSE-2.0>)
+//!   (./LICENSE-APACHE or <http://www.apache.org/licenses/LICENSE-2.0>)
 //! * MIT license (./LICENSE-MIT or <http://opensource.org/licenses/MIT>)
-//! at your option.
+//!   at your option.
 //!
 //! ## Examples
 //!
@@ -18,14 +18,41 @@
 //! ```
 //!
 
+pub mod ac;
+pub mod charmap;
+pub mod dict;
+pub mod double_array;
+pub mod entry;
+pub mod error;
+pub mod frozen;
+pub mod image;
+pub mod iter;
+pub mod normalize;
+pub mod replace;
+pub mod reverse;
+pub mod segment;
 pub mod tokenizer;
 
-use std::str::Chars;
+use std::ops::ControlFlow;
+use std::slice;
+use std::vec;
+
+use iter::{IntoIter, Iter, Keys, Values, ValuesMut};
+use normalize::Normalize;
 
 use tokenizer::AllTokenizer;
+use tokenizer::CharGroups;
+use tokenizer::CommonPrefixes;
+use tokenizer::GroupTokenizer;
 use tokenizer::MaxFrontTokenizer;
+use tokenizer::Token;
+use tokenizer::WithGaps;
 
 pub type Tokenizer<'a, T> = tokenizer::Tokenizer<'a, T>;
+pub type Entry<'a, T> = entry::Entry<'a, T>;
+pub type ReverseTrie<T> = reverse::ReverseTrie<T>;
+pub type DoubleArrayTrie<T> = double_array::DoubleArrayTrie<T>;
+pub type Error = error::Error;
 
 /// 叶子结点状态，
 /// Not 不是一个词
@@ -39,14 +66,82 @@ pub(crate) enum Status {
     LastEnd,
 }
 
+/// 基本多文种平面（BMP）的字符数量，Big 节点为这部分字符预分配槽位
+const BMP_SIZE: usize = 0x10000;
+
 /// 叶子结点，包含一个字符，状态，值，和子节点
 /// Big 是一个大的节点，用于存储所有的字符，可以有效加速查询和词典加载速度，但是较耗费空间
 #[derive(Debug)]
 enum Leafs<T> {
-    Big(Vec<Option<Trie<T>>>),
+    Big(Box<BigLeafs<T>>),
     Small(Vec<Trie<T>>),
 }
 
+/// Big 节点的存储，BMP 字符按码位直接寻址，
+/// 辅助平面字符（如 CJK 扩展 B 区汉字、emoji）存放在有序数组中，下标从 BMP_SIZE 开始编号
+#[derive(Debug)]
+struct BigLeafs<T> {
+    bmp: Vec<Option<Trie<T>>>,
+    supplementary: Vec<Trie<T>>,
+}
+
+impl<T> BigLeafs<T> {
+    fn new() -> Self {
+        Self {
+            bmp: (0..BMP_SIZE).map(|_| None).collect(),
+            supplementary: Vec::new(),
+        }
+    }
+
+    fn binary_search(&self, c: char) -> Result<usize, usize> {
+        let index = c as usize;
+        if index < BMP_SIZE {
+            if self.bmp[index].is_none() {
+                Err(index)
+            } else {
+                Ok(index)
+            }
+        } else {
+            match self.supplementary.binary_search_by(|t| t.c.cmp(&c)) {
+                Ok(i) => Ok(BMP_SIZE + i),
+                Err(i) => Err(BMP_SIZE + i),
+            }
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<&Trie<T>> {
+        if index < BMP_SIZE {
+            self.bmp[index].as_ref()
+        } else {
+            self.supplementary.get(index - BMP_SIZE)
+        }
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut Trie<T>> {
+        if index < BMP_SIZE {
+            self.bmp[index].as_mut()
+        } else {
+            self.supplementary.get_mut(index - BMP_SIZE)
+        }
+    }
+
+    fn insert(&mut self, index: usize, c: Trie<T>) {
+        if index < BMP_SIZE {
+            self.bmp[index] = Some(c);
+        } else {
+            self.supplementary.insert(index - BMP_SIZE, c);
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        if index < BMP_SIZE {
+            self.bmp[index] = None;
+        } else {
+            self.supplementary.remove(index - BMP_SIZE);
+        }
+    }
+}
+
 impl<T> Default for Leafs<T> {
     fn default() -> Self {
         Leafs::Small(Vec::new())
@@ -56,35 +151,42 @@ impl<T> Default for Leafs<T> {
 impl<T> Leafs<T> {
     fn binary_search(&self, c: char) -> Result<usize, usize> {
         match self {
-            Leafs::Big(v) => {
-                let index = c as usize;
-                if v[index].is_none() {
-                    Err(index)
-                } else {
-                    Ok(index)
-                }
+            Leafs::Big(v) => v.binary_search(c),
+            Leafs::Small(v) => v.binary_search_by(|t| t.c.cmp(&c)),
+        }
+    }
+
+    /// 插入时使用的查找，先和最后一个子节点比较，
+    /// 按字符顺序插入时可以直接追加，不需要二分查找和移动元素
+    fn search_for_insert(&self, c: char) -> Result<usize, usize> {
+        if let Leafs::Small(v) = self {
+            match v.last() {
+                None => return Err(0),
+                Some(last) if last.c < c => return Err(v.len()),
+                Some(last) if last.c == c => return Ok(v.len() - 1),
+                _ => {}
             }
-            Leafs::Small(v) => v.binary_search_by(|t| c.cmp(&t.c)),
         }
+        self.binary_search(c)
     }
 
     fn get_mut(&mut self, index: usize) -> &mut Trie<T> {
         match self {
-            Leafs::Big(v) => v[index].as_mut().unwrap(),
+            Leafs::Big(v) => v.get_mut(index).unwrap(),
             Leafs::Small(v) => v.get_mut(index).unwrap(),
         }
     }
 
     pub fn get_uncheck(&self, index: usize) -> &Trie<T> {
         match self {
-            Leafs::Big(v) => v[index].as_ref().unwrap(),
+            Leafs::Big(v) => v.get(index).unwrap(),
             Leafs::Small(v) => v.get(index).unwrap(),
         }
     }
 
     pub fn get(&self, index: usize) -> Option<&Trie<T>> {
         match self {
-            Leafs::Big(v) => v.get(index)?.as_ref(),
+            Leafs::Big(v) => v.get(index),
             Leafs::Small(v) => v.get(index),
         }
     }
@@ -92,7 +194,7 @@ impl<T> Leafs<T> {
     fn insert(&mut self, index: usize, c: Trie<T>) {
         match self {
             Leafs::Big(v) => {
-                v[index] = Some(c);
+                v.insert(index, c);
             }
             Leafs::Small(v) => {
                 v.insert(index, c);
@@ -100,6 +202,42 @@ impl<T> Leafs<T> {
         }
     }
 
+    fn remove(&mut self, index: usize) {
+        match self {
+            Leafs::Big(v) => {
+                v.remove(index);
+            }
+            Leafs::Small(v) => {
+                v.remove(index);
+            }
+        }
+    }
+
+    /// 按字符顺序遍历子节点
+    fn iter(&self) -> LeafsIter<'_, T> {
+        match self {
+            Leafs::Big(v) => LeafsIter::Big(v.bmp.iter(), v.supplementary.iter()),
+            Leafs::Small(v) => LeafsIter::Small(v.iter()),
+        }
+    }
+
+    fn iter_mut(&mut self) -> LeafsIterMut<'_, T> {
+        match self {
+            Leafs::Big(v) => LeafsIterMut::Big(v.bmp.iter_mut(), v.supplementary.iter_mut()),
+            Leafs::Small(v) => LeafsIterMut::Small(v.iter_mut()),
+        }
+    }
+
+    fn into_iter(self) -> LeafsIntoIter<T> {
+        match self {
+            Leafs::Big(v) => {
+                let BigLeafs { bmp, supplementary } = *v;
+                LeafsIntoIter::Big(bmp.into_iter(), supplementary.into_iter())
+            }
+            Leafs::Small(v) => LeafsIntoIter::Small(v.into_iter()),
+        }
+    }
+
     fn is_empty(&self) -> bool {
         match self {
             Leafs::Big(_) => false,
@@ -108,6 +246,64 @@ impl<T> Leafs<T> {
     }
 }
 
+/// 子节点迭代器，按字符顺序输出
+enum LeafsIter<'a, T> {
+    Big(slice::Iter<'a, Option<Trie<T>>>, slice::Iter<'a, Trie<T>>),
+    Small(slice::Iter<'a, Trie<T>>),
+}
+
+impl<'a, T> Iterator for LeafsIter<'a, T> {
+    type Item = &'a Trie<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            LeafsIter::Big(bmp, supplementary) => bmp
+                .find_map(Option::as_ref)
+                .or_else(|| supplementary.next()),
+            LeafsIter::Small(v) => v.next(),
+        }
+    }
+}
+
+enum LeafsIterMut<'a, T> {
+    Big(
+        slice::IterMut<'a, Option<Trie<T>>>,
+        slice::IterMut<'a, Trie<T>>,
+    ),
+    Small(slice::IterMut<'a, Trie<T>>),
+}
+
+impl<'a, T> Iterator for LeafsIterMut<'a, T> {
+    type Item = &'a mut Trie<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            LeafsIterMut::Big(bmp, supplementary) => bmp
+                .find_map(Option::as_mut)
+                .or_else(|| supplementary.next()),
+            LeafsIterMut::Small(v) => v.next(),
+        }
+    }
+}
+
+enum LeafsIntoIter<T> {
+    Big(vec::IntoIter<Option<Trie<T>>>, vec::IntoIter<Trie<T>>),
+    Small(vec::IntoIter<Trie<T>>),
+}
+
+impl<T> Iterator for LeafsIntoIter<T> {
+    type Item = Trie<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            LeafsIntoIter::Big(bmp, supplementary) => {
+                bmp.find_map(|t| t).or_else(|| supplementary.next())
+            }
+            LeafsIntoIter::Small(v) => v.next(),
+        }
+    }
+}
+
 /// Trie 树，用于存储词典
 /// # Examples
 /// ```rust
@@ -117,49 +313,131 @@ impl<T> Leafs<T> {
 /// assert_eq!(trie.get("中国人"), Some("cns").as_ref());
 /// assert_eq!(trie.get("中国"), None);
 ///
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Trie<T> {
     c: char,
     status: Status,
+    normalize: Normalize,
     value: Option<T>,
     leafs: Leafs<T>,
 }
 
+impl<T> Default for Trie<T> {
+    fn default() -> Self {
+        Trie::new('\0', Normalize::NONE)
+    }
+}
+
 impl<T> Trie<T> {
     /// 创建一个新的 Trie 树, 对于超大词典作了优化，可以有效加速词典加载速度，但是较耗费空间
+    /// 支持完整的 Unicode 范围，BMP 以外的字符（如 emoji、CJK 扩展 B 区汉字）同样可以作为词插入
     /// # Examples
     /// ```rust
     /// use char_trie::Trie;
     /// let mut trie = Trie::new_big();
     /// trie.insert("中国人", "cns");
+    /// trie.insert("𠀀𠀁", "ext-b");
     /// assert_eq!(trie.get("中国人"), Some("cns").as_ref());
     /// assert_eq!(trie.get("中国"), None);
+    /// assert_eq!(trie.get("𠀀𠀁"), Some("ext-b").as_ref());
+    /// let tokens: Vec<_> = trie.iter_max("😀中国人𠀀𠀁").map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["中国人", "𠀀𠀁"]);
     /// ```
     pub fn new_big() -> Self {
         Self {
             c: '\0',
             status: Status::Not,
+            normalize: Normalize::NONE,
             value: None,
-            leafs: Leafs::Big((0..65536).map(|_| None).collect()),
+            leafs: Leafs::Big(Box::new(BigLeafs::new())),
         }
     }
 
-    /// 插入一个词到trie树中
+    /// 设置字符归一化选项，插入时作用于词，查询和分词时作用于文本，
+    /// 返回的 Token 仍然指向原文，遍历得到的词是归一化之后的
+    /// # Panics
+    /// 树中已经有词时 panic，归一化选项需要在插入之前设置
+    /// # Examples
+    /// ```rust
+    /// use char_trie::normalize::Normalize;
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default().with_normalize(Normalize::FULL_WIDTH | Normalize::ASCII_CASE);
+    /// trie.insert("ABC", "abc");
+    /// assert_eq!(trie.get("abc"), Some(&"abc"));
+    /// let text = "我的ＡＢＣ和Abc";
+    /// let tokens: Vec<_> = trie.iter_max(text).map(|t| (t.0, t.1)).collect();
+    /// assert_eq!(tokens, vec![("ＡＢＣ", (6, 15)), ("Abc", (18, 21))]);
+    /// assert_eq!(trie.keys().collect::<Vec<_>>(), vec!["abc"]);
+    /// ```
+    pub fn with_normalize(mut self, normalize: Normalize) -> Self {
+        assert!(
+            self.is_empty(),
+            "normalize must be set before inserting words"
+        );
+        self.normalize = normalize;
+        self
+    }
+
+    /// 字符归一化选项
+    pub fn normalize(&self) -> Normalize {
+        self.normalize
+    }
+
+    /// 插入一个词到trie树中，如果词已经存在，返回被替换掉的旧值
     /// key 词
     /// value 词的值
     /// # Examples
     /// ```rust
     /// use char_trie::Trie;
     /// let mut trie = Trie::default();
-    /// trie.insert("中国人", "cns");
+    /// assert_eq!(trie.insert("中国人", "cns"), None);
     /// assert_eq!(trie.get("中国人"), Some("cns").as_ref());
     /// assert_eq!(trie.get("中国"), None);
+    /// assert_eq!(trie.insert("中国人", "ud"), Some("cns"));
     /// ```
-    pub fn insert(&mut self, key: &str, value: T) {
+    pub fn insert(&mut self, key: &str, value: T) -> Option<T> {
         if key.is_empty() {
-            return;
+            return None;
+        }
+        self.inner_insert(key.chars(), value).1
+    }
+
+    /// 插入一个词，与 [`Trie::insert`] 相同，但是空字符串会返回 [`Error::EmptyKey`] 而不是被忽略
+    /// # Examples
+    /// ```rust
+    /// use char_trie::{Error, Trie};
+    /// let mut trie = Trie::default();
+    /// assert_eq!(trie.try_insert("中国人", "cns").unwrap(), None);
+    /// assert!(matches!(trie.try_insert("", "empty"), Err(Error::EmptyKey)));
+    /// ```
+    pub fn try_insert(&mut self, key: &str, value: T) -> error::Result<Option<T>> {
+        if key.is_empty() {
+            return Err(Error::EmptyKey);
+        }
+        Ok(self.inner_insert(key.chars(), value).1)
+    }
+
+    /// 获取词对应的入口，用于原地修改或者插入
+    /// # Panics
+    /// key 为空时 panic
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// for word in ["中国", "中国人", "中国"] {
+    ///     trie.entry(word).and_modify(|freq| *freq += 1).or_insert(1);
+    /// }
+    /// assert_eq!(trie.get("中国"), Some(&2));
+    /// assert_eq!(trie.get("中国人"), Some(&1));
+    /// ```
+    pub fn entry<'a>(&'a mut self, key: &'a str) -> Entry<'a, T> {
+        assert!(!key.is_empty(), "entry key must not be empty");
+        if self.get(key).is_some() {
+            let trie = self.inner_get_mut(key.chars()).unwrap();
+            Entry::Occupied(entry::OccupiedEntry { trie })
+        } else {
+            Entry::Vacant(entry::VacantEntry { root: self, key })
         }
-        self.inner_insert(key.chars(), value)
     }
 
     pub fn get(&self, key: &str) -> Option<&T> {
@@ -175,26 +453,72 @@ impl<T> Trie<T> {
         }
     }
 
-    fn new(c: char) -> Self {
+    /// 获取词对应值的可变引用
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", 1);
+    /// *trie.get_mut("中国人").unwrap() += 1;
+    /// assert_eq!(trie.get("中国人"), Some(&2));
+    /// assert_eq!(trie.get_mut("中国"), None);
+    /// ```
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut T> {
+        if key.is_empty() {
+            return None;
+        }
+
+        let trie = self.inner_get_mut(key.chars())?;
+        if trie.status == Status::End || trie.status == Status::LastEnd {
+            trie.value.as_mut()
+        } else {
+            None
+        }
+    }
+
+    /// 从trie树中删除一个词，返回词的值
+    /// 删除后会裁剪掉不再包含任何词的子节点，并修复祖先节点的状态
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// assert_eq!(trie.remove("中国人"), Some("cns"));
+    /// assert_eq!(trie.remove("中国人"), None);
+    /// assert_eq!(trie.get("中国"), Some("cn").as_ref());
+    /// let tokens: Vec<_> = trie.iter_max("我是中国人").map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["中国"]);
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<T> {
+        if key.is_empty() {
+            return None;
+        }
+        self.inner_remove(key.chars())
+    }
+
+    fn new(c: char, normalize: Normalize) -> Self {
         Trie {
             c,
             status: Status::Not,
+            normalize,
             value: None,
             leafs: Leafs::Small(Vec::new()),
         }
     }
 
-    fn inner_insert(&mut self, cs: Chars<'_>, value: T) {
+    fn inner_insert(&mut self, cs: impl Iterator<Item = char>, value: T) -> (&mut Self, Option<T>) {
         let mut trie = self;
 
         let mut has_end = false;
 
         for c in cs {
+            let c = trie.normalize.apply(c);
             if has_end {
                 trie.status = Status::End;
                 has_end = false;
             }
-            match trie.leafs.binary_search(c) {
+            match trie.leafs.search_for_insert(c) {
                 Ok(index) => {
                     trie = trie.leafs.get_mut(index);
                     if trie.status == Status::LastEnd {
@@ -202,25 +526,55 @@ impl<T> Trie<T> {
                     }
                 }
                 Err(index) => {
-                    trie.leafs.insert(index, Trie::new(c));
+                    trie.leafs.insert(index, Trie::new(c, trie.normalize));
                     trie = trie.leafs.get_mut(index);
                 }
             }
         }
 
-        trie.value = Some(value);
+        let old = trie.value.replace(value);
         if trie.leafs.is_empty() {
             trie.status = Status::LastEnd;
         } else {
             trie.status = Status::End;
         }
+        (trie, old)
+    }
+
+    fn inner_remove(&mut self, mut cs: impl Iterator<Item = char>) -> Option<T> {
+        let c = match cs.next() {
+            Some(c) => self.normalize.apply(c),
+            None => {
+                if self.status == Status::Not {
+                    return None;
+                }
+                self.status = Status::Not;
+                return self.value.take();
+            }
+        };
+
+        let index = self.leafs.binary_search(c).ok()?;
+        let trie = self.leafs.get_mut(index);
+        let value = trie.inner_remove(cs)?;
+
+        // 子节点既不是词也没有后继，直接裁剪
+        if trie.status == Status::Not && trie.leafs.is_empty() {
+            self.leafs.remove(index);
+        }
+
+        // 没有后继的词，状态修复为 LastEnd
+        if self.status == Status::End && self.leafs.is_empty() {
+            self.status = Status::LastEnd;
+        }
+
+        Some(value)
     }
 
-    fn inner_get(&self, cs: Chars<'_>) -> Option<&Self> {
+    fn inner_get(&self, cs: impl Iterator<Item = char>) -> Option<&Self> {
         let mut trie = self;
 
         for c in cs {
-            match trie.leafs.binary_search(c) {
+            match trie.leafs.binary_search(trie.normalize.apply(c)) {
                 Ok(index) => {
                     trie = trie.leafs.get_uncheck(index);
                 }
@@ -233,13 +587,257 @@ impl<T> Trie<T> {
         Some(trie)
     }
 
+    fn inner_get_mut(&mut self, cs: impl Iterator<Item = char>) -> Option<&mut Self> {
+        let mut trie = self;
+
+        for c in cs {
+            match trie.leafs.binary_search(trie.normalize.apply(c)) {
+                Ok(index) => {
+                    trie = trie.leafs.get_mut(index);
+                }
+                Err(_) => {
+                    return None;
+                }
+            }
+        }
+
+        Some(trie)
+    }
+
+    /// 词典中词的数量，需要遍历整棵树
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// assert!(trie.is_empty());
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// assert_eq!(trie.len(), 2);
+    /// assert!(!trie.is_empty());
+    /// ```
+    pub fn len(&self) -> usize {
+        self.values().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values().next().is_none()
+    }
+
+    /// 按字符顺序遍历词典中所有的 (词, 值)
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("国人", "gr");
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// let words: Vec<_> = trie.iter().collect();
+    /// assert_eq!(
+    ///     words,
+    ///     vec![
+    ///         ("中国".to_string(), &"cn"),
+    ///         ("中国人".to_string(), &"cns"),
+    ///         ("国人".to_string(), &"gr"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self, String::new())
+    }
+
+    /// 按字符顺序遍历词典中所有的词
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let trie: Trie<()> = {
+    ///     let mut trie = Trie::new_big();
+    ///     trie.insert("国人", ());
+    ///     trie.insert("中国", ());
+    ///     trie
+    /// };
+    /// assert_eq!(trie.keys().collect::<Vec<_>>(), vec!["中国", "国人"]);
+    /// ```
+    pub fn keys(&self) -> Keys<'_, T> {
+        Keys::new(self.iter())
+    }
+
+    /// 按词的字符顺序遍历词典中所有的值
+    pub fn values(&self) -> Values<'_, T> {
+        Values::new(self)
+    }
+
+    /// 按词的字符顺序遍历词典中所有值的可变引用
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", 1);
+    /// trie.insert("中国", 2);
+    /// trie.values_mut().for_each(|v| *v *= 10);
+    /// assert_eq!(trie.values().collect::<Vec<_>>(), vec![&20, &10]);
+    /// ```
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T> {
+        ValuesMut::new(self)
+    }
+
+    /// 前缀查询，按字符顺序返回所有以 prefix 开头的词及其值
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// trie.insert("中华", "zh");
+    /// trie.insert("国人", "gr");
+    /// let words: Vec<_> = trie.iter_prefix("中").map(|(k, _)| k).collect();
+    /// assert_eq!(words, vec!["中华", "中国", "中国人"]);
+    /// let words: Vec<_> = trie.iter_prefix("中国").collect();
+    /// assert_eq!(words, vec![("中国".to_string(), &"cn"), ("中国人".to_string(), &"cns")]);
+    /// assert_eq!(trie.iter_prefix("美国").next(), None);
+    ///
+    /// // 返回的词是归一化后的形式，与 keys 一致
+    /// use char_trie::normalize::Normalize;
+    /// let mut trie = Trie::default().with_normalize(Normalize::ASCII_CASE);
+    /// trie.insert("abc", 1);
+    /// assert_eq!(trie.iter_prefix("AB").collect::<Vec<_>>(), vec![("abc".to_string(), &1)]);
+    /// ```
+    pub fn iter_prefix(&self, prefix: &str) -> Iter<'_, T> {
+        match self.inner_get(prefix.chars()) {
+            Some(trie) => {
+                let prefix = prefix.chars().map(|c| self.normalize.apply(c)).collect();
+                Iter::new(trie, prefix)
+            }
+            None => Iter::empty(),
+        }
+    }
+
     pub fn char_get(&self, c: char) -> Option<&Self> {
-        match self.leafs.binary_search(c) {
+        match self.leafs.binary_search(self.normalize.apply(c)) {
             Ok(index) => self.leafs.get(index),
             Err(_) => None,
         }
     }
 
+    /// 公共前缀查询，按长度从短到长返回词典中所有是 text 前缀的词，结果为 (字节长度, 值)
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// trie.insert("中", "z");
+    /// let prefixes: Vec<_> = trie.common_prefixes("中国人民").collect();
+    /// assert_eq!(prefixes, vec![(3, &"z"), (6, &"cn"), (9, &"cns")]);
+    /// assert_eq!(trie.common_prefixes("我是中国人").next(), None);
+    /// ```
+    pub fn common_prefixes<'a>(&'a self, text: &'a str) -> CommonPrefixes<'a, T> {
+        CommonPrefixes::new(self, text)
+    }
+
+    /// 最长前缀查询，返回词典中是 text 前缀的最长的词，结果为 (字节长度, 值)
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// assert_eq!(trie.longest_prefix("中国人民"), Some((9, &"cns")));
+    /// assert_eq!(trie.longest_prefix("中国队"), Some((6, &"cn")));
+    /// assert_eq!(trie.longest_prefix("美国"), None);
+    /// ```
+    pub fn longest_prefix<'a>(&'a self, text: &'a str) -> Option<(usize, &'a T)> {
+        self.common_prefixes(text).last()
+    }
+
+    /// 文本中是否包含词典中的词，找到第一个词就返回
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国", "cn");
+    /// assert!(trie.contains_any("我是中国人"));
+    /// assert!(!trie.contains_any("我是美国人"));
+    /// ```
+    pub fn contains_any(&self, text: &str) -> bool {
+        text.char_indices().any(|(i, _)| {
+            self.try_for_each_prefix(&text[i..], |_, _| ControlFlow::Break(()))
+                .is_break()
+        })
+    }
+
+    /// 找出起始位置最靠前的词，起始位置相同时取最短的词，与 `iter_all(text).next()` 结果相同
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// trie.insert("是中", "sz");
+    /// assert_eq!(trie.find_first("我是中国人"), Some(("是中", (3, 9), Some(&"sz"))));
+    /// assert_eq!(trie.find_first("中国人"), Some(("中国", (0, 6), Some(&"cn"))));
+    /// assert_eq!(trie.find_first("美国"), None);
+    /// ```
+    pub fn find_first<'a>(&'a self, text: &'a str) -> Option<Token<'a, T>> {
+        text.char_indices().find_map(|(start, _)| {
+            let shortest = self.try_for_each_prefix(&text[start..], |len, value| {
+                ControlFlow::Break((len, value))
+            });
+            match shortest {
+                ControlFlow::Break((len, value)) => {
+                    let end = start + len;
+                    Some((&text[start..end], (start, end), Some(value)))
+                }
+                ControlFlow::Continue(()) => None,
+            }
+        })
+    }
+
+    /// 文本中词的出现次数，包括重叠的词，与 `iter_all(text).count()` 结果相同
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("中国", "cn");
+    /// trie.insert("国人", "gr");
+    /// assert_eq!(trie.count_matches("中国人，中国"), 4);
+    /// ```
+    pub fn count_matches(&self, text: &str) -> usize {
+        let mut count = 0;
+        for (i, _) in text.char_indices() {
+            let _ = self.try_for_each_prefix(&text[i..], |_, _| {
+                count += 1;
+                ControlFlow::<()>::Continue(())
+            });
+        }
+        count
+    }
+
+    /// 按长度从短到长把 text 中是词的前缀交给 f，结果为 (字节长度, 值)，f 返回 Break 时提前结束。
+    /// 直接沿子节点查找，不分配内存，供只需要判断或计数的查询使用
+    fn try_for_each_prefix<'a, B>(
+        &'a self,
+        text: &str,
+        mut f: impl FnMut(usize, &'a T) -> ControlFlow<B>,
+    ) -> ControlFlow<B> {
+        let mut trie = self;
+        for (i, c) in text.char_indices() {
+            match trie.char_get(c) {
+                Some(child) => trie = child,
+                None => break,
+            }
+            if trie.status != Status::Not {
+                if let Some(value) = &trie.value {
+                    f(i + c.len_utf8(), value)?;
+                }
+            }
+            if trie.status == Status::LastEnd {
+                break;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
     /// 实现了全词匹配， 如词典中包含 【中国，国人，中国人】 三个词，那么对于文本 “我是中国人” 将返回
     /// [中国，国人，中国人]
     /// # Examples
@@ -273,4 +871,201 @@ impl<T> Trie<T> {
     pub fn iter_max<'a>(&'a self, text: &'a str) -> MaxFrontTokenizer<'a, T> {
         MaxFrontTokenizer::new(self, text)
     }
+
+    /// 正向最大匹配，同时输出没有匹配到词的文本，输出覆盖整个文本
+    /// # Examples
+    /// ```rust
+    /// use char_trie::tokenizer::TokenKind;
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// let tokens: Vec<_> = trie
+    ///     .iter_max_with_gaps("我是中国人。")
+    ///     .map(|(t, kind)| (t.0, kind))
+    ///     .collect();
+    /// assert_eq!(
+    ///     tokens,
+    ///     vec![
+    ///         ("我是", TokenKind::Gap),
+    ///         ("中国人", TokenKind::Word),
+    ///         ("。", TokenKind::Gap),
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter_max_with_gaps<'a>(
+        &'a self,
+        text: &'a str,
+    ) -> WithGaps<'a, T, MaxFrontTokenizer<'a, T>> {
+        self.iter_max(text).with_gaps()
+    }
+
+    /// 先把字母、数字、空白等按字符类型成组，其余文本做正向最大匹配，输出覆盖整个文本
+    /// # Examples
+    /// ```rust
+    /// use char_trie::tokenizer::{CharGroups, TokenKind};
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("售价", "n");
+    /// trie.insert("元", "q");
+    /// trie.insert("Pro", "nz");
+    /// let tokens: Vec<_> = trie
+    ///     .iter_max_grouped("iPhone 15 Pro 售价6999元", CharGroups::default())
+    ///     .filter(|(_, kind)| *kind != TokenKind::Whitespace)
+    ///     .map(|(t, kind)| (t.0, t.2, kind))
+    ///     .collect();
+    /// assert_eq!(
+    ///     tokens,
+    ///     vec![
+    ///         ("iPhone", None, TokenKind::Latin),
+    ///         ("15", None, TokenKind::Number),
+    ///         ("Pro", Some(&"nz"), TokenKind::Latin),
+    ///         ("售价", Some(&"n"), TokenKind::Word),
+    ///         ("6999", None, TokenKind::Number),
+    ///         ("元", Some(&"q"), TokenKind::Word),
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter_max_grouped<'a>(
+        &'a self,
+        text: &'a str,
+        groups: CharGroups,
+    ) -> GroupTokenizer<'a, T> {
+        GroupTokenizer::new(self, text, groups)
+    }
+
+    /// 全词匹配，同时输出没有被任何词覆盖的文本
+    /// # Examples
+    /// ```rust
+    /// use char_trie::tokenizer::TokenKind;
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国", "cn");
+    /// trie.insert("国人", "gr");
+    /// let tokens: Vec<_> = trie
+    ///     .iter_all_with_gaps("我是中国人")
+    ///     .map(|(t, kind)| (t.0, kind))
+    ///     .collect();
+    /// assert_eq!(
+    ///     tokens,
+    ///     vec![
+    ///         ("我是", TokenKind::Gap),
+    ///         ("中国", TokenKind::Word),
+    ///         ("国人", TokenKind::Word),
+    ///     ]
+    /// );
+    /// ```
+    pub fn iter_all_with_gaps<'a>(&'a self, text: &'a str) -> WithGaps<'a, T, AllTokenizer<'a, T>> {
+        self.iter_all(text).with_gaps()
+    }
+}
+
+/// 词典的查询和分词接口，[`Trie`]、[`DoubleArrayTrie`] 和 [`frozen::TypedFrozenTrie`] 都实现了它，
+/// 值的类型相同，调用方可以在不同的实现之间切换。
+/// [`frozen::FrozenTrie`] 也实现了它，值为编码后的字节
+/// # Examples
+/// ```rust
+/// use char_trie::{Dictionary, DoubleArrayTrie, Trie};
+/// fn words<D: Dictionary>(dict: &D, text: &str) -> Vec<String> {
+///     dict.iter_max(text).map(|t| t.0.to_string()).collect()
+/// }
+/// let trie: Trie<_> = [("中国", 1), ("中国人", 2)].into_iter().collect();
+/// let expected = words(&trie, "我是中国人");
+/// let dat = DoubleArrayTrie::from(trie);
+/// assert_eq!(words(&dat, "我是中国人"), expected);
+/// assert_eq!(Dictionary::get(&dat, "中国"), Some(&1));
+/// ```
+pub trait Dictionary {
+    type Value: ?Sized;
+
+    fn get(&self, key: &str) -> Option<&Self::Value>;
+
+    /// 正向最大匹配
+    fn iter_max<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Token<'a, Self::Value>>;
+
+    /// 全词匹配
+    fn iter_all<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Token<'a, Self::Value>>;
+}
+
+impl<T> Dictionary for Trie<T> {
+    type Value = T;
+
+    fn get(&self, key: &str) -> Option<&T> {
+        Trie::get(self, key)
+    }
+
+    fn iter_max<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Token<'a, T>> {
+        Trie::iter_max(self, text)
+    }
+
+    fn iter_all<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Token<'a, T>> {
+        Trie::iter_all(self, text)
+    }
+}
+
+/// 批量构建 Trie 树，先按词排序再依次插入，子节点都是追加到末尾，适合加载大词典
+/// 重复的词以最后出现的值为准
+/// # Examples
+/// ```rust
+/// use char_trie::Trie;
+/// let trie: Trie<_> = [("中国人", "cns"), ("国人", "gr"), ("中国", "cn")]
+///     .into_iter()
+///     .collect();
+/// assert_eq!(trie.get("中国"), Some(&"cn"));
+/// assert_eq!(trie.len(), 3);
+/// ```
+impl<K: AsRef<str>, T> FromIterator<(K, T)> for Trie<T> {
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let mut trie = Trie::default();
+        trie.extend(iter);
+        trie
+    }
+}
+
+/// 批量插入，先按词排序再依次插入，重复的词以最后出现的值为准
+/// # Examples
+/// ```rust
+/// use char_trie::Trie;
+/// let mut trie = Trie::new_big();
+/// trie.extend(vec![("中国人", 1), ("中国", 2)]);
+/// trie.extend(vec![(String::from("中国"), 3)]);
+/// assert_eq!(trie.get("中国"), Some(&3));
+/// ```
+impl<K: AsRef<str>, T> Extend<(K, T)> for Trie<T> {
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, iter: I) {
+        let mut items: Vec<(K, T)> = iter.into_iter().collect();
+        // 稳定排序，保证重复的词按出现顺序插入
+        items.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()));
+        for (key, value) in items {
+            self.insert(key.as_ref(), value);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Trie<T> {
+    type Item = (String, &'a T);
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// 按字符顺序消费整棵树，输出所有的 (词, 值)
+/// # Examples
+/// ```rust
+/// use char_trie::Trie;
+/// let mut trie = Trie::default();
+/// trie.insert("中国人", String::from("cns"));
+/// trie.insert("中国", String::from("cn"));
+/// let words: Vec<(String, String)> = trie.into_iter().collect();
+/// assert_eq!(words[0], ("中国".to_string(), "cn".to_string()));
+/// assert_eq!(words.len(), 2);
+/// ```
+impl<T> IntoIterator for Trie<T> {
+    type Item = (String, T);
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self)
+    }
 }
@@ -0,0 +1,140 @@
+use std::ops::{BitOr, BitOrAssign};
+
+mod table;
+
+/// 字符归一化选项，可以用 `|` 组合。
+/// 插入时作用于词，查询和分词时作用于文本，返回的 Token 仍然指向原文
+/// # Examples
+/// ```rust
+/// use char_trie::normalize::Normalize;
+/// let n = Normalize::FULL_WIDTH | Normalize::ASCII_CASE;
+/// assert_eq!(n.apply('Ａ'), 'a');
+/// assert_eq!(n.apply('中'), '中');
+/// assert_eq!(Normalize::NONE.apply('A'), 'A');
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Normalize(u8);
+
+impl Normalize {
+    /// 不做归一化
+    pub const NONE: Normalize = Normalize(0);
+    /// ASCII 大写字母转小写
+    pub const ASCII_CASE: Normalize = Normalize(1);
+    /// Unicode 简单大小写折叠（CaseFolding.txt 中 C 和 S 类的映射），如 Ａ → ａ、ς → σ、ſ → s。
+    /// 只有完整折叠的字符（如 ß → ss）保持不变
+    /// # Examples
+    /// ```rust
+    /// use char_trie::normalize::Normalize;
+    /// assert_eq!(Normalize::UNICODE_CASE.apply('Σ'), 'σ');
+    /// assert_eq!(Normalize::UNICODE_CASE.apply('ς'), 'σ');
+    /// assert_eq!(Normalize::UNICODE_CASE.apply('ß'), 'ß');
+    /// ```
+    pub const UNICODE_CASE: Normalize = Normalize(1 << 1);
+    /// 全角 ASCII 字符转半角，全角空格转为半角空格
+    pub const FULL_WIDTH: Normalize = Normalize(1 << 2);
+    /// NFKC 兼容折叠中结果为单个字符的映射，包含 FULL_WIDTH，
+    /// 覆盖全角和半角字符、CJK 兼容汉字、康熙部首、单字符的罗马数字、上下标和带圈数字 ①-⑨ 等，
+    /// 映射表由 Unicode 14.0 数据生成。
+    /// 结果为多个字符的情况（如 ﬁ、㈱、⑩、Ⅻ）以及组合多个字符（如 ｶﾞ）不处理，保证文本位置一一对应
+    /// # Examples
+    /// ```rust
+    /// use char_trie::normalize::Normalize;
+    /// assert_eq!(Normalize::COMPAT.apply('\u{F901}'), '更');
+    /// assert_eq!(Normalize::COMPAT.apply('⼀'), '一');
+    /// assert_eq!(Normalize::COMPAT.apply('Ⅳ'), 'Ⅳ');
+    /// assert_eq!(Normalize::COMPAT.apply('Ⅴ'), 'V');
+    /// assert_eq!(Normalize::COMPAT.apply('ｶ'), 'カ');
+    /// assert_eq!(Normalize::COMPAT.apply('①'), '1');
+    /// assert_eq!(Normalize::COMPAT.apply('ﬁ'), 'ﬁ');
+    /// ```
+    pub const COMPAT: Normalize = Normalize(1 << 3 | 1 << 2);
+
+    pub fn contains(self, other: Normalize) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub(crate) fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// 由 bits 还原，包含未定义的位时返回 None
+    pub(crate) fn from_bits(bits: u8) -> Option<Normalize> {
+        let all = Normalize::ASCII_CASE | Normalize::UNICODE_CASE | Normalize::COMPAT;
+        (bits & !all.0 == 0).then_some(Normalize(bits))
+    }
+
+    /// 对单个字符做归一化
+    pub fn apply(self, c: char) -> char {
+        if self.is_empty() {
+            return c;
+        }
+
+        let mut c = c;
+        if self.contains(Normalize::COMPAT) {
+            c = compat(c);
+        } else if self.contains(Normalize::FULL_WIDTH) {
+            c = full_width(c);
+        }
+
+        if self.contains(Normalize::UNICODE_CASE) {
+            c = case_fold(c);
+        } else if self.contains(Normalize::ASCII_CASE) {
+            c = c.to_ascii_lowercase();
+        }
+        c
+    }
+}
+
+impl BitOr for Normalize {
+    type Output = Normalize;
+
+    fn bitor(self, rhs: Normalize) -> Normalize {
+        Normalize(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Normalize {
+    fn bitor_assign(&mut self, rhs: Normalize) {
+        self.0 |= rhs.0;
+    }
+}
+
+fn full_width(c: char) -> char {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        _ => c,
+    }
+}
+
+fn compat(c: char) -> char {
+    if c < '\u{A0}' {
+        return c;
+    }
+    lookup(table::COMPAT, c).unwrap_or(c)
+}
+
+fn case_fold(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+    if let Some(folded) = lookup(table::CASE_FOLD, c) {
+        return folded;
+    }
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+fn lookup(table: &[(char, char)], c: char) -> Option<char> {
+    table
+        .binary_search_by_key(&c, |&(from, _)| from)
+        .ok()
+        .map(|i| table[i].1)
+}
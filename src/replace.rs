@@ -0,0 +1,147 @@
+//! 按匹配结果改写文本，用于敏感词替换、打码
+//!
+//! [`Trie::replace_all`] 和 [`Trie::mask`] 使用正向最大匹配，匹配结果互不重叠；
+//! [`Trie::mask_all`] 使用全词匹配，重叠的匹配先合并为一段再替换，参见 [`MergedSpans`]。
+//! 需要跳过填充字符等匹配选项时，可以用 [`replace_with`] 改写任意分词器的结果
+
+use std::borrow::Cow;
+
+use crate::tokenizer::{AllTokenizer, Token};
+use crate::Trie;
+
+impl<T> Trie<T> {
+    /// 用正向最大匹配找出文本中的词，每个词替换为 f 的返回值，其余文本保持不变
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国", "China");
+    /// trie.insert("人", "people");
+    /// let text = trie.replace_all("我是中国人", |t| t.2.copied().unwrap_or_default().into());
+    /// assert_eq!(text, "我是Chinapeople");
+    /// ```
+    pub fn replace_all<'a, F>(&'a self, text: &'a str, f: F) -> String
+    where
+        F: FnMut(Token<'a, T>) -> Cow<'a, str>,
+    {
+        replace_with(text, self.iter_max(text), f)
+    }
+
+    /// 用正向最大匹配找出文本中的词，词的每个字符替换为 mask，替换后字符数不变
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("坏词", ());
+    /// assert_eq!(trie.mask("这是坏词，坏词", '*'), "这是**，**");
+    /// ```
+    pub fn mask(&self, text: &str, mask: char) -> String {
+        replace_with(text, self.iter_max(text), |t| mask_str(t.0, mask).into())
+    }
+
+    /// 用全词匹配找出文本中的词，重叠的词合并为一段，每个字符替换为 mask
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国", ());
+    /// trie.insert("国人民", ());
+    /// // 正向最大匹配只能找到 “中国”
+    /// assert_eq!(trie.mask("中国人民银行", '*'), "**人民银行");
+    /// assert_eq!(trie.mask_all("中国人民银行", '*'), "****银行");
+    /// ```
+    pub fn mask_all(&self, text: &str, mask: char) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end) in MergedSpans::new(self.iter_all(text)) {
+            out.push_str(&text[last..start]);
+            out.extend(text[start..end].chars().map(|_| mask));
+            last = end;
+        }
+        out.push_str(&text[last..]);
+        out
+    }
+}
+
+/// 把 tokens 对应的文本替换为 f 的返回值，tokens 需要按起始位置排列且互不重叠，
+/// 与前面的词重叠的词会被忽略
+/// # Examples
+/// ```rust
+/// use char_trie::replace::replace_with;
+/// use char_trie::tokenizer::Fillers;
+/// use char_trie::Trie;
+/// let mut trie = Trie::default();
+/// trie.insert("坏词", ());
+/// let fillers = Fillers::default();
+/// let text = "坏 词";
+/// let tokens = trie.iter_max(text).skip_fillers(&fillers);
+/// assert_eq!(replace_with(text, tokens, |_| "[已屏蔽]".into()), "[已屏蔽]");
+/// ```
+pub fn replace_with<'a, T, I, F>(text: &'a str, tokens: I, mut f: F) -> String
+where
+    T: 'a,
+    I: IntoIterator<Item = Token<'a, T>>,
+    F: FnMut(Token<'a, T>) -> Cow<'a, str>,
+{
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for token in tokens {
+        let (start, end) = token.1;
+        if start < last {
+            continue;
+        }
+        out.push_str(&text[last..start]);
+        out.push_str(&f(token));
+        last = end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+fn mask_str(word: &str, mask: char) -> String {
+    word.chars().map(|_| mask).collect()
+}
+
+/// 合并全词匹配中重叠的词，输出合并后的 (start, end)，按位置排列。
+/// 只是首尾相接的词不合并
+/// # Examples
+/// ```rust
+/// use char_trie::replace::MergedSpans;
+/// use char_trie::Trie;
+/// let trie: Trie<_> = [("中国", 1), ("国人", 2), ("人民", 3), ("银行", 4)].into_iter().collect();
+/// let spans: Vec<_> = MergedSpans::new(trie.iter_all("中国人民银行")).collect();
+/// assert_eq!(spans, vec![(0, 12), (12, 18)]);
+/// ```
+pub struct MergedSpans<'a, T> {
+    inner: AllTokenizer<'a, T>,
+    current: Option<(usize, usize)>,
+}
+
+impl<'a, T> MergedSpans<'a, T> {
+    pub fn new(inner: AllTokenizer<'a, T>) -> Self {
+        Self {
+            inner,
+            current: None,
+        }
+    }
+}
+
+impl<T> Iterator for MergedSpans<'_, T> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (_, (start, end), _) in self.inner.by_ref() {
+            match &mut self.current {
+                Some((_, current_end)) if start < *current_end => {
+                    *current_end = (*current_end).max(end);
+                }
+                current => {
+                    if let Some(span) = current.replace((start, end)) {
+                        return Some(span);
+                    }
+                }
+            }
+        }
+        self.current.take()
+    }
+}
@@ -0,0 +1,194 @@
+use crate::error::{self, Error};
+use crate::normalize::Normalize;
+use crate::tokenizer::MaxBackTokenizer;
+use crate::{Leafs, Status, Trie};
+
+/// 逆向 Trie 树，词按字符倒序存储，用于逆向最大匹配
+/// # Examples
+/// ```rust
+/// use char_trie::ReverseTrie;
+/// let mut trie = ReverseTrie::default();
+/// trie.insert("结合", "v");
+/// trie.insert("合成", "v");
+/// trie.insert("成分", "n");
+/// trie.insert("分子", "n");
+/// trie.insert("结", "v");
+/// let text = "结合成分子";
+/// let tokens: Vec<_> = trie.iter_max(text).map(|t| t.0).collect();
+/// assert_eq!(tokens, vec!["结", "合成", "分子"]);
+/// ```
+#[derive(Debug)]
+pub struct ReverseTrie<T> {
+    trie: Trie<T>,
+}
+
+impl<T> Default for ReverseTrie<T> {
+    fn default() -> Self {
+        Self {
+            trie: Trie::default(),
+        }
+    }
+}
+
+impl<T> ReverseTrie<T> {
+    /// 创建一个新的逆向 Trie 树，根节点使用 Big 结构，参见 [`Trie::new_big`]
+    pub fn new_big() -> Self {
+        Self {
+            trie: Trie::new_big(),
+        }
+    }
+
+    /// 设置字符归一化选项，参见 [`Trie::with_normalize`]
+    pub fn with_normalize(self, normalize: Normalize) -> Self {
+        Self {
+            trie: self.trie.with_normalize(normalize),
+        }
+    }
+
+    /// 插入一个词，如果词已经存在，返回被替换掉的旧值
+    pub fn insert(&mut self, key: &str, value: T) -> Option<T> {
+        if key.is_empty() {
+            return None;
+        }
+        self.trie.inner_insert(key.chars().rev(), value).1
+    }
+
+    /// 插入一个词，空字符串返回 [`Error::EmptyKey`]，参见 [`Trie::try_insert`]
+    pub fn try_insert(&mut self, key: &str, value: T) -> error::Result<Option<T>> {
+        if key.is_empty() {
+            return Err(Error::EmptyKey);
+        }
+        Ok(self.trie.inner_insert(key.chars().rev(), value).1)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&T> {
+        if key.is_empty() {
+            return None;
+        }
+
+        let trie = self.trie.inner_get(key.chars().rev())?;
+        if trie.status == Status::End || trie.status == Status::LastEnd {
+            trie.value.as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut T> {
+        if key.is_empty() {
+            return None;
+        }
+
+        let trie = self.trie.inner_get_mut(key.chars().rev())?;
+        if trie.status == Status::End || trie.status == Status::LastEnd {
+            trie.value.as_mut()
+        } else {
+            None
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<T> {
+        if key.is_empty() {
+            return None;
+        }
+        self.trie.inner_remove(key.chars().rev())
+    }
+
+    /// 词典中词的数量，需要遍历整棵树
+    pub fn len(&self) -> usize {
+        self.trie.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trie.is_empty()
+    }
+
+    /// 最长后缀查询，返回词典中是 text 后缀的最长的词，结果为 (字节长度, 值)
+    /// # Examples
+    /// ```rust
+    /// use char_trie::ReverseTrie;
+    /// let mut trie = ReverseTrie::default();
+    /// trie.insert("中国人", "cns");
+    /// trie.insert("国人", "gr");
+    /// assert_eq!(trie.longest_suffix("我是中国人"), Some((9, &"cns")));
+    /// assert_eq!(trie.longest_suffix("外国人"), Some((6, &"gr")));
+    /// assert_eq!(trie.longest_suffix("中国"), None);
+    /// ```
+    pub fn longest_suffix<'a>(&'a self, text: &'a str) -> Option<(usize, &'a T)> {
+        let mut trie = &self.trie;
+        let mut longest = None;
+
+        for (i, c) in text.char_indices().rev() {
+            match trie.char_get(c) {
+                Some(t) => {
+                    trie = t;
+                    match t.status {
+                        Status::Not => continue,
+                        Status::End => {}
+                        Status::LastEnd => return t.value.as_ref().map(|v| (text.len() - i, v)),
+                    }
+                    if let Some(value) = t.value.as_ref() {
+                        longest = Some((text.len() - i, value));
+                    }
+                }
+                None => break,
+            }
+        }
+
+        longest
+    }
+
+    /// 实现了逆向最大匹配，从文本末尾开始向前匹配，结果按文本顺序返回
+    /// 如词典中包含 【结合，合成，成分，分子，结】，那么对于文本 “结合成分子” 将返回
+    /// [结，合成，分子]
+    pub fn iter_max<'a>(&'a self, text: &'a str) -> MaxBackTokenizer<'a, T> {
+        MaxBackTokenizer::new(self, text)
+    }
+}
+
+/// 由正向 Trie 树转换
+/// 保留 Trie 树的归一化选项和根节点结构
+/// # Examples
+/// ```rust
+/// use char_trie::normalize::Normalize;
+/// use char_trie::{ReverseTrie, Trie};
+/// let mut trie = Trie::default().with_normalize(Normalize::ASCII_CASE);
+/// trie.insert("abc", 1);
+/// let reverse = ReverseTrie::from(trie);
+/// assert_eq!(reverse.get("ABC"), Some(&1));
+/// ```
+impl<T> From<Trie<T>> for ReverseTrie<T> {
+    fn from(trie: Trie<T>) -> Self {
+        let reverse = match trie.leafs {
+            Leafs::Big(_) => ReverseTrie::new_big(),
+            Leafs::Small(_) => ReverseTrie::default(),
+        };
+        let mut reverse = reverse.with_normalize(trie.normalize);
+        reverse.extend(trie);
+        reverse
+    }
+}
+
+impl<K: AsRef<str>, T> FromIterator<(K, T)> for ReverseTrie<T> {
+    fn from_iter<I: IntoIterator<Item = (K, T)>>(iter: I) -> Self {
+        let mut trie = ReverseTrie::default();
+        trie.extend(iter);
+        trie
+    }
+}
+
+/// 批量插入，先按倒序后的词排序再依次插入，重复的词以最后出现的值为准
+impl<K: AsRef<str>, T> Extend<(K, T)> for ReverseTrie<T> {
+    fn extend<I: IntoIterator<Item = (K, T)>>(&mut self, iter: I) {
+        let mut items: Vec<(String, T)> = iter
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().chars().rev().collect(), v))
+            .collect();
+        items.sort_by(|a, b| a.0.cmp(&b.0));
+        for (key, value) in items {
+            if !key.is_empty() {
+                self.trie.inner_insert(key.chars(), value);
+            }
+        }
+    }
+}
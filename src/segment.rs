@@ -0,0 +1,298 @@
+use crate::tokenizer::Token;
+use crate::{ReverseTrie, Trie};
+
+/// 词的权重函数
+type WeightFn<'a, T> = dyn Fn(&T) -> f64 + 'a;
+
+/// 双向最大匹配，分别做正向和逆向最大匹配，按以下规则选出一种切分：
+/// 1. 设置了权重函数时，词典权重总和高的优先
+/// 2. 词数少的优先，没有匹配到词的字符按单字计数
+/// 3. 单字少的优先
+/// 4. 仍然相同时取逆向最大匹配的结果
+/// # Examples
+/// ```rust
+/// use char_trie::segment::BiMaxSegmenter;
+/// use char_trie::{ReverseTrie, Trie};
+/// let words = [("结合", 1), ("合成", 1), ("成分", 1), ("分子", 1), ("结", 1)];
+/// let forward: Trie<_> = words.into_iter().collect();
+/// let backward: ReverseTrie<_> = words.into_iter().collect();
+/// let segmenter = BiMaxSegmenter::new(&forward, &backward);
+/// let tokens: Vec<_> = segmenter.segment("结合成分子").into_iter().map(|t| t.0).collect();
+/// // 正向为 [结合，成分，子]，逆向为 [结，合成，分子]，词数相同，逆向单字少
+/// assert_eq!(tokens, vec!["结", "合成", "分子"]);
+/// ```
+pub struct BiMaxSegmenter<'a, T> {
+    forward: &'a Trie<T>,
+    backward: &'a ReverseTrie<T>,
+    weight: Option<Box<WeightFn<'a, T>>>,
+}
+
+impl<'a, T> BiMaxSegmenter<'a, T> {
+    /// forward 和 backward 应当包含相同的词
+    pub fn new(forward: &'a Trie<T>, backward: &'a ReverseTrie<T>) -> Self {
+        Self {
+            forward,
+            backward,
+            weight: None,
+        }
+    }
+
+    /// 设置词的权重函数，两个方向的结果优先按权重总和比较
+    /// # Examples
+    /// ```rust
+    /// use char_trie::segment::BiMaxSegmenter;
+    /// use char_trie::{ReverseTrie, Trie};
+    /// let words = [("结合", 100), ("合成", 1), ("成分", 100), ("分子", 1), ("结", 1)];
+    /// let forward: Trie<_> = words.into_iter().collect();
+    /// let backward: ReverseTrie<_> = words.into_iter().collect();
+    /// let segmenter = BiMaxSegmenter::new(&forward, &backward).weight(|freq| *freq as f64);
+    /// let tokens: Vec<_> = segmenter.segment("结合成分子").into_iter().map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["结合", "成分"]);
+    /// ```
+    pub fn weight<F: Fn(&T) -> f64 + 'a>(mut self, weight: F) -> Self {
+        self.weight = Some(Box::new(weight));
+        self
+    }
+
+    pub fn segment<'b>(&'b self, text: &'b str) -> Vec<Token<'b, T>> {
+        let forward: Vec<_> = self.forward.iter_max(text).collect();
+        let backward: Vec<_> = self.backward.iter_max(text).collect();
+
+        if forward.iter().map(|t| t.1).eq(backward.iter().map(|t| t.1)) {
+            return backward;
+        }
+
+        if let Some(weight) = &self.weight {
+            let f = total_weight(&forward, weight);
+            let b = total_weight(&backward, weight);
+            if f > b {
+                return forward;
+            }
+            if b > f {
+                return backward;
+            }
+        }
+
+        let f = Stats::new(text, &forward);
+        let b = Stats::new(text, &backward);
+        if (f.words, f.singles) < (b.words, b.singles) {
+            forward
+        } else {
+            backward
+        }
+    }
+}
+
+fn total_weight<T>(tokens: &[Token<'_, T>], weight: &WeightFn<'_, T>) -> f64 {
+    tokens.iter().filter_map(|t| t.2).map(weight).sum()
+}
+
+/// 一种切分的统计，没有匹配到词的字符按单字计数
+struct Stats {
+    words: usize,
+    singles: usize,
+}
+
+impl Stats {
+    fn new<T>(text: &str, tokens: &[Token<'_, T>]) -> Self {
+        let mut stats = Stats {
+            words: 0,
+            singles: 0,
+        };
+        let mut covered = 0;
+        for (word, (start, end), _) in tokens {
+            stats.add_gap(&text[covered..*start]);
+            stats.words += 1;
+            if word.chars().nth(1).is_none() {
+                stats.singles += 1;
+            }
+            covered = *end;
+        }
+        stats.add_gap(&text[covered..]);
+        stats
+    }
+
+    fn add_gap(&mut self, gap: &str) {
+        let n = gap.chars().count();
+        self.words += n;
+        self.singles += n;
+    }
+}
+
+/// 没有匹配到词的单字默认权重，远低于常见词频取对数后的权重
+const DEFAULT_UNKNOWN_WEIGHT: f64 = -100.0;
+
+/// 基于词图的最大概率切分，用公共前缀查询构建文本的有向无环词图，
+/// 再用动态规划找出权重总和最大的路径
+///
+/// 权重函数通常返回词频的对数（即对数概率），没有以某个字开头的词时，
+/// 该字按单字切分，权重为 [`DagSegmenter::unknown_weight`]
+/// # Examples
+/// ```rust
+/// use char_trie::segment::DagSegmenter;
+/// use char_trie::Trie;
+/// let trie: Trie<_> = [("结合", 10), ("合成", 20), ("成分", 30), ("分子", 40), ("结", 5)]
+///     .into_iter()
+///     .collect();
+/// let segmenter = DagSegmenter::new(&trie, |freq| (*freq as f64).ln());
+/// let tokens: Vec<_> = segmenter.segment("结合成分子").into_iter().map(|t| t.0).collect();
+/// assert_eq!(tokens, vec!["结", "合成", "分子"]);
+/// ```
+pub struct DagSegmenter<'a, T> {
+    trie: &'a Trie<T>,
+    weight: Box<WeightFn<'a, T>>,
+    unknown: f64,
+}
+
+impl<'a, T> DagSegmenter<'a, T> {
+    pub fn new<F: Fn(&T) -> f64 + 'a>(trie: &'a Trie<T>, weight: F) -> Self {
+        Self {
+            trie,
+            weight: Box::new(weight),
+            unknown: DEFAULT_UNKNOWN_WEIGHT,
+        }
+    }
+
+    /// 设置没有匹配到词的单字的权重，默认为 -100.0
+    pub fn unknown_weight(mut self, weight: f64) -> Self {
+        self.unknown = weight;
+        self
+    }
+
+    pub fn segment<'b>(&'b self, text: &'b str) -> Vec<Token<'b, T>> {
+        self.k_best(text, 1)
+            .pop()
+            .map(|(_, tokens)| tokens)
+            .unwrap_or_default()
+    }
+
+    /// 返回权重总和最大的 k 种切分及其权重，按权重从高到低排列，
+    /// 权重相同时第一个不同的词更长的优先
+    /// # Examples
+    /// ```rust
+    /// use char_trie::segment::DagSegmenter;
+    /// use char_trie::Trie;
+    /// let trie: Trie<_> = [("中国", 3.0), ("国人", 2.0), ("中", 1.0), ("人", 1.0)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let segmenter = DagSegmenter::new(&trie, |w| *w);
+    /// let best = segmenter.k_best("中国人", 2);
+    /// let paths: Vec<_> = best
+    ///     .iter()
+    ///     .map(|(score, tokens)| (*score, tokens.iter().map(|t| t.0).collect::<Vec<_>>()))
+    ///     .collect();
+    /// assert_eq!(paths, vec![(4.0, vec!["中国", "人"]), (3.0, vec!["中", "国人"])]);
+    /// ```
+    pub fn k_best<'b>(&'b self, text: &'b str, k: usize) -> Vec<(f64, Vec<Token<'b, T>>)> {
+        let lattice = self.lattice(text);
+
+        // paths[i] 为从位置 i 到文本末尾权重最大的 k 条路径，
+        // 每条路径记录 (权重, 第一条边的下标, 在下一个位置中的排名)
+        let mut paths: Vec<Vec<(f64, usize, usize)>> = vec![Vec::new(); text.len() + 1];
+        paths[text.len()].push((0.0, 0, 0));
+        for start in (0..text.len()).rev() {
+            let edges = lattice.edges_from(start);
+            let mut candidates = Vec::new();
+            for (i, (_, (_, end), value)) in edges.iter().enumerate() {
+                let weight = self.edge_weight(*value);
+                for (rank, (score, _, _)) in paths[*end].iter().enumerate() {
+                    candidates.push((weight + score, i, rank));
+                }
+            }
+            candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.cmp(&a.1)));
+            candidates.truncate(k);
+            paths[start] = candidates;
+        }
+
+        paths[0]
+            .iter()
+            .map(|&(score, mut i, mut rank)| {
+                let mut tokens = Vec::new();
+                let mut start = 0;
+                while start < text.len() {
+                    let token = lattice.edges_from(start)[i];
+                    start = token.1 .1;
+                    (_, i, rank) = paths[start][rank];
+                    tokens.push(token);
+                }
+                (score, tokens)
+            })
+            .collect()
+    }
+
+    /// 构建文本的词图，参见 [`Lattice`]
+    pub fn lattice<'b>(&'b self, text: &'b str) -> Lattice<'b, T> {
+        Lattice::new(self.trie, text)
+    }
+
+    fn edge_weight(&self, value: Option<&T>) -> f64 {
+        value.map_or(self.unknown, |v| (self.weight)(v))
+    }
+}
+
+/// 文本的词图，节点为字符边界的字节位置，边为词典中的词。
+/// 没有以某个字开头的词时，加入一条该单字的边，值为 None，保证从文本开头总能走到末尾
+/// # Examples
+/// ```rust
+/// use char_trie::segment::Lattice;
+/// use char_trie::Trie;
+/// let trie: Trie<_> = [("中国", 1), ("国人", 2), ("中国人", 3)].into_iter().collect();
+/// let lattice = Lattice::new(&trie, "中国人民");
+/// assert_eq!(lattice.nodes().collect::<Vec<_>>(), vec![0, 3, 6, 9, 12]);
+/// let edges: Vec<_> = lattice.edges().map(|t| (t.0, t.1)).collect();
+/// assert_eq!(
+///     edges,
+///     vec![
+///         ("中国", (0, 6)),
+///         ("中国人", (0, 9)),
+///         ("国人", (3, 9)),
+///         ("人", (6, 9)),
+///         ("民", (9, 12)),
+///     ]
+/// );
+/// assert_eq!(lattice.edges_from(3).len(), 1);
+/// ```
+pub struct Lattice<'a, T> {
+    text: &'a str,
+    edges: Vec<Vec<Token<'a, T>>>,
+}
+
+impl<'a, T> Lattice<'a, T> {
+    pub fn new(trie: &'a Trie<T>, text: &'a str) -> Self {
+        let mut edges: Vec<Vec<Token<'a, T>>> = (0..text.len()).map(|_| Vec::new()).collect();
+        for (start, c) in text.char_indices() {
+            let from = &mut edges[start];
+            for (len, value) in trie.common_prefixes(&text[start..]) {
+                let end = start + len;
+                from.push((&text[start..end], (start, end), Some(value)));
+            }
+            if from.is_empty() {
+                let end = start + c.len_utf8();
+                from.push((&text[start..end], (start, end), None));
+            }
+        }
+        Self { text, edges }
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// 所有节点，即每个字符的起始位置以及文本末尾
+    pub fn nodes(&self) -> impl Iterator<Item = usize> + 'a {
+        self.text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(self.text.len()))
+    }
+
+    /// 所有的边，按起始位置、再按结束位置排列
+    pub fn edges(&self) -> impl Iterator<Item = &Token<'a, T>> {
+        self.edges.iter().flatten()
+    }
+
+    /// 从 start 位置出发的边，按结束位置从小到大排列，start 不是节点时为空
+    pub fn edges_from(&self, start: usize) -> &[Token<'a, T>] {
+        self.edges.get(start).map_or(&[], Vec::as_slice)
+    }
+}
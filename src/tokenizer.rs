@@ -1,12 +1,168 @@
-use crate::{Status, Trie};
+use std::collections::HashSet;
+use std::str::CharIndices;
+
+use crate::charmap::CharMap;
+use crate::{ReverseTrie, Status, Trie};
 
 /// Token is a tuple of (word, (start, end), value)
 pub type Token<'a, T> = (&'a str, (usize, usize), Option<&'a T>);
 
+/// 分词时的匹配选项
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MatchOptions<'a> {
+    word_boundary: bool,
+    equivalence: Option<&'a CharMap>,
+    fillers: Option<&'a Fillers>,
+}
+
+/// 公共前缀查询，按长度从短到长输出词典中所有是文本前缀的词，
+/// 结果为 (词在文本中的字节长度, 值)
+pub struct CommonPrefixes<'a, T> {
+    cursor: Cursor<'a, T>,
+    chars: CharIndices<'a>,
+    options: MatchOptions<'a>,
+    /// 已经匹配到字符，之后才能跳过填充字符
+    matched: bool,
+    /// 上一个匹配的字符之后连续跳过的填充字符数
+    gap: usize,
+}
+
+/// 当前匹配到的节点
+enum Cursor<'a, T> {
+    Single(&'a Trie<T>),
+    /// 使用等价表时原字符和转换后的字符可能分别走到不同的节点，
+    /// 按原字符优先的顺序排列
+    Multi(Vec<&'a Trie<T>>),
+}
+
+impl<'a, T> CommonPrefixes<'a, T> {
+    pub fn new(trie: &'a Trie<T>, text: &'a str) -> Self {
+        Self::with_options(trie, text, MatchOptions::default())
+    }
+
+    pub(crate) fn with_options(
+        trie: &'a Trie<T>,
+        text: &'a str,
+        options: MatchOptions<'a>,
+    ) -> Self {
+        let cursor = match options.equivalence {
+            Some(_) => Cursor::Multi(vec![trie]),
+            None => Cursor::Single(trie),
+        };
+        Self {
+            cursor,
+            chars: text.char_indices(),
+            options,
+            matched: false,
+            gap: 0,
+        }
+    }
+
+    /// 匹配时文本中的字符同时按原字符和等价表转换后的字符查找，参见 [`CharMap`]。
+    /// 同样长度的前缀两种写法都是词时，取与原文写法相同的词
+    pub fn equivalence(mut self, map: &'a CharMap) -> Self {
+        self.options.equivalence = Some(map);
+        if let Cursor::Single(trie) = self.cursor {
+            self.cursor = Cursor::Multi(vec![trie]);
+        }
+        self
+    }
+
+    /// 词的字符之间允许夹杂填充字符，参见 [`Fillers`]
+    pub fn skip_fillers(mut self, fillers: &'a Fillers) -> Self {
+        self.options.fillers = Some(fillers);
+        self
+    }
+
+    /// 沿字符 c 前进一步，没有对应的子节点时返回 None，
+    /// 否则返回 (之后是否还可能匹配, 到达的词的值)
+    fn step(&mut self, c: char) -> Option<(bool, Option<&'a T>)> {
+        match &mut self.cursor {
+            Cursor::Single(trie) => {
+                let child = trie.char_get(c)?;
+                *trie = child;
+                Some((child.status != Status::LastEnd, word_value(child)))
+            }
+            Cursor::Multi(nodes) => {
+                let mapped = self
+                    .options
+                    .equivalence
+                    .and_then(|map| map.get(c))
+                    .filter(|&mapped| mapped != c);
+                let count = nodes.len();
+                // 同一深度的不同节点没有公共的子节点，不需要去重
+                for k in 0..count {
+                    let node = nodes[k];
+                    for c in Some(c).into_iter().chain(mapped) {
+                        if let Some(child) = node.char_get(c) {
+                            nodes.push(child);
+                        }
+                    }
+                }
+                if nodes.len() == count {
+                    return None;
+                }
+                nodes.drain(..count);
+                let value = nodes.iter().find_map(|node| word_value(node));
+                // 没有后继的节点不会再匹配更长的词
+                nodes.retain(|node| node.status != Status::LastEnd);
+                Some((!nodes.is_empty(), value))
+            }
+        }
+    }
+
+    fn can_skip(&self, c: char) -> bool {
+        match self.options.fillers {
+            Some(fillers) => self.matched && self.gap < fillers.max_gap && fillers.contains(c),
+            None => false,
+        }
+    }
+
+    /// 后面不可能再有匹配，提前结束
+    fn finish(&mut self) {
+        self.chars = "".char_indices();
+    }
+}
+
+/// 节点是词时返回词的值
+fn word_value<T>(trie: &Trie<T>) -> Option<&T> {
+    match trie.status {
+        Status::Not => None,
+        Status::End | Status::LastEnd => trie.value.as_ref(),
+    }
+}
+
+impl<'a, T> Iterator for CommonPrefixes<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((i, c)) = self.chars.next() {
+            let Some((more, value)) = self.step(c) else {
+                if self.can_skip(c) {
+                    self.gap += 1;
+                    continue;
+                }
+                self.finish();
+                return None;
+            };
+            self.matched = true;
+            self.gap = 0;
+            if !more {
+                self.finish();
+            }
+            if let Some(value) = value {
+                return Some((i + c.len_utf8(), value));
+            }
+        }
+        None
+    }
+}
+
 pub struct MaxFrontTokenizer<'a, T> {
     text: &'a str,
     root: &'a Trie<T>,
     start: usize,
+    options: MatchOptions<'a>,
 }
 
 impl<'a, T> MaxFrontTokenizer<'a, T> {
@@ -15,78 +171,92 @@ impl<'a, T> MaxFrontTokenizer<'a, T> {
             text,
             root: trie,
             start: 0,
+            options: MatchOptions::default(),
         }
     }
+
+    /// 字母、数字等空格分词的文字组成的词，只在单词边界处匹配，参见 [`on_word_boundary`]
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("he", "he");
+    /// trie.insert("hello", "hello");
+    /// let text = "the hell, he said";
+    /// let tokens: Vec<_> = trie.iter_max(text).word_boundary(true).map(|t| t.1).collect();
+    /// assert_eq!(tokens, vec![(10, 12)]);
+    /// ```
+    pub fn word_boundary(mut self, word_boundary: bool) -> Self {
+        self.options.word_boundary = word_boundary;
+        self
+    }
+
+    /// 匹配时文本中的字符同时按原字符和等价表转换后的字符查找，返回的 Token 仍然指向原文，参见 [`CharMap`]
+    /// # Examples
+    /// ```rust
+    /// use char_trie::charmap::CharMap;
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("中国人", "cns");
+    /// let map: CharMap = [('國', '国')].into_iter().collect();
+    /// let tokens: Vec<_> = trie.iter_max("我是中國人").equivalence(&map).map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["中國人"]);
+    /// ```
+    pub fn equivalence(mut self, map: &'a CharMap) -> Self {
+        self.options.equivalence = Some(map);
+        self
+    }
+
+    /// 词的字符之间允许夹杂填充字符，返回的 Token 包含其中的填充字符，参见 [`Fillers`]
+    /// # Examples
+    /// ```rust
+    /// use char_trie::tokenizer::Fillers;
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("敏感词", 1);
+    /// let fillers = Fillers::default();
+    /// let text = "这是敏 感*词。";
+    /// let tokens: Vec<_> = trie.iter_max(text).skip_fillers(&fillers).map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["敏 感*词"]);
+    /// ```
+    pub fn skip_fillers(mut self, fillers: &'a Fillers) -> Self {
+        self.options.fillers = Some(fillers);
+        self
+    }
+
+    /// 同时输出没有被词覆盖的文本，参见 [`WithGaps`]
+    pub fn with_gaps(self) -> WithGaps<'a, T, Self> {
+        WithGaps::new(self.text, self)
+    }
 }
 
 impl<'a, T> Iterator for MaxFrontTokenizer<'a, T> {
     type Item = Token<'a, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut temp_end = 0;
-        let mut temp_value = None;
-        let mut temp_trie = self.root;
-        let mut first_len = 0;
-
         loop {
-            if self.start >= self.text.len() {
-                return None;
-            }
-
-            let cs = self.text[self.start..].char_indices();
-
-            for (i, c) in cs {
-                let c_len = c.len_utf8();
-                if first_len == 0 {
-                    first_len = c_len;
+            let rest = &self.text[self.start..];
+            let longest = CommonPrefixes::with_options(self.root, rest, self.options)
+                .filter(|(len, _)| {
+                    !self.options.word_boundary
+                        || on_word_boundary(self.text, self.start, self.start + len)
+                })
+                .last();
+            match longest {
+                Some((len, value)) => {
+                    let start = self.start;
+                    self.start += len;
+                    return Some((
+                        &self.text[start..self.start],
+                        (start, self.start),
+                        Some(value),
+                    ));
                 }
-
-                match temp_trie.char_get(c) {
-                    Some(trie) => match trie.status {
-                        Status::End => {
-                            temp_end = self.start + i + c_len;
-                            temp_value = trie.value.as_ref();
-                            temp_trie = trie;
-                            continue;
-                        }
-                        Status::LastEnd => {
-                            let start = self.start;
-                            let end: usize = self.start + i + c_len;
-                            self.start = end;
-                            return Some((
-                                &self.text[start..end],
-                                (start, end),
-                                trie.value.as_ref(),
-                            ));
-                        }
-                        Status::Not => {
-                            temp_trie = trie;
-                            continue;
-                        }
-                    },
-                    None => {
-                        if temp_end > 0 {
-                            let start = self.start;
-                            self.start = temp_end;
-                            return Some((
-                                &self.text[start..temp_end],
-                                (start, temp_end),
-                                temp_value,
-                            ));
-                        } else {
-                            self.start += first_len;
-                            temp_trie = self.root;
-                            first_len = 0;
-                            break;
-                        }
-                    }
+                None => {
+                    // 没有以当前字符开头的词，跳过一个字符
+                    self.start += rest.chars().next()?.len_utf8();
                 }
             }
-            if temp_end > 0 {
-                let start = self.start;
-                self.start = temp_end;
-                return Some((&self.text[start..temp_end], (start, temp_end), temp_value));
-            }
         }
     }
 }
@@ -94,10 +264,9 @@ impl<'a, T> Iterator for MaxFrontTokenizer<'a, T> {
 pub struct AllTokenizer<'a, T> {
     text: &'a str,
     root: &'a Trie<T>,
-    trie: &'a Trie<T>,
     start: usize,
-    end: usize,
-    first_len: usize,
+    prefixes: CommonPrefixes<'a, T>,
+    options: MatchOptions<'a>,
 }
 
 impl<'a, T> AllTokenizer<'a, T> {
@@ -105,73 +274,638 @@ impl<'a, T> AllTokenizer<'a, T> {
         Self {
             text,
             root: trie,
-            trie,
             start: 0,
-            end: 0,
-            first_len: 0,
+            prefixes: trie.common_prefixes(text),
+            options: MatchOptions::default(),
         }
     }
+
+    /// 修改匹配选项后，当前位置重新开始匹配
+    fn set_options(mut self, options: MatchOptions<'a>) -> Self {
+        self.options = options;
+        self.prefixes =
+            CommonPrefixes::with_options(self.root, &self.text[self.start..], self.options);
+        self
+    }
+
+    /// 字母、数字等空格分词的文字组成的词，只在单词边界处匹配，参见 [`on_word_boundary`]
+    /// # Examples
+    /// ```rust
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("he", "he");
+    /// trie.insert("中国", "cn");
+    /// let text = "the中国he";
+    /// let tokens: Vec<_> = trie.iter_all(text).word_boundary(true).map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["中国", "he"]);
+    /// ```
+    pub fn word_boundary(self, word_boundary: bool) -> Self {
+        let options = MatchOptions {
+            word_boundary,
+            ..self.options
+        };
+        self.set_options(options)
+    }
+
+    /// 匹配时文本中的字符同时按原字符和等价表转换后的字符查找，返回的 Token 仍然指向原文，
+    /// 参见 [`CharMap`]。同样长度的前缀两种写法都是词时，取与原文写法相同的词
+    /// # Examples
+    /// ```rust
+    /// use char_trie::charmap::CharMap;
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("后", 1);
+    /// trie.insert("後天", 2);
+    /// let map: CharMap = [('後', '后')].into_iter().collect();
+    /// let tokens: Vec<_> = trie.iter_all("後天").equivalence(&map).map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["後", "後天"]);
+    ///
+    /// // 两种写法都是词时，取与原文写法相同的词
+    /// trie.insert("后天", 3);
+    /// let tokens: Vec<_> = trie.iter_all("後天").equivalence(&map).map(|t| t.2).collect();
+    /// assert_eq!(tokens, vec![Some(&1), Some(&2)]);
+    /// ```
+    pub fn equivalence(self, map: &'a CharMap) -> Self {
+        let options = MatchOptions {
+            equivalence: Some(map),
+            ..self.options
+        };
+        self.set_options(options)
+    }
+
+    /// 词的字符之间允许夹杂填充字符，返回的 Token 包含其中的填充字符，参见 [`Fillers`]
+    /// # Examples
+    /// ```rust
+    /// use char_trie::tokenizer::Fillers;
+    /// use char_trie::Trie;
+    /// let mut trie = Trie::default();
+    /// trie.insert("敏感", 1);
+    /// trie.insert("敏感词", 2);
+    /// // 最多连续跳过 1 个填充字符
+    /// let fillers = Fillers::from_chars(['*', '.'], 1);
+    /// let text = "敏.感*词 敏..感";
+    /// let tokens: Vec<_> = trie.iter_all(text).skip_fillers(&fillers).map(|t| t.0).collect();
+    /// assert_eq!(tokens, vec!["敏.感", "敏.感*词"]);
+    /// ```
+    pub fn skip_fillers(self, fillers: &'a Fillers) -> Self {
+        let options = MatchOptions {
+            fillers: Some(fillers),
+            ..self.options
+        };
+        self.set_options(options)
+    }
+
+    /// 同时输出没有被任何词覆盖的文本，参见 [`WithGaps`]
+    pub fn with_gaps(self) -> WithGaps<'a, T, Self> {
+        WithGaps::new(self.text, self)
+    }
 }
 
 impl<'a, T> Iterator for AllTokenizer<'a, T> {
     type Item = Token<'a, T>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut first = true;
         loop {
-            if self.start >= self.text.len() {
-                return None;
+            for (len, value) in self.prefixes.by_ref() {
+                let end = self.start + len;
+                if self.options.word_boundary && !on_word_boundary(self.text, self.start, end) {
+                    continue;
+                }
+                return Some((&self.text[self.start..end], (self.start, end), Some(value)));
             }
 
-            let cs = self.text[self.end..].char_indices();
+            // 当前位置的词已经输出完，从下一个字符重新开始
+            let c = self.text[self.start..].chars().next()?;
+            self.start += c.len_utf8();
+            self.prefixes =
+                CommonPrefixes::with_options(self.root, &self.text[self.start..], self.options);
+        }
+    }
+}
 
-            for (i, c) in cs {
-                let c_len = c.len_utf8();
-                if first {
-                    self.first_len = c_len;
-                    first = false;
+/// 默认最多连续跳过的填充字符数
+const DEFAULT_MAX_GAP: usize = 3;
+
+/// 匹配时可以跳过的填充字符，用于识别 “敏 感*词” 这类在字中间插入符号的写法。
+/// 词的第一个字之后，连续不超过 max_gap 个填充字符可以被跳过，
+/// 填充字符本身是词典中的下一个字时按字匹配
+///
+/// 默认的填充字符为空白、标点和零宽字符，最多连续跳过 3 个
+#[derive(Debug, Clone)]
+pub struct Fillers {
+    chars: Option<HashSet<char>>,
+    max_gap: usize,
+}
+
+impl Default for Fillers {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_GAP)
+    }
+}
+
+impl Fillers {
+    /// 使用默认的填充字符
+    pub fn new(max_gap: usize) -> Self {
+        Self {
+            chars: None,
+            max_gap,
+        }
+    }
+
+    /// 使用自定义的填充字符
+    pub fn from_chars<I: IntoIterator<Item = char>>(chars: I, max_gap: usize) -> Self {
+        Self {
+            chars: Some(chars.into_iter().collect()),
+            max_gap,
+        }
+    }
+
+    pub fn max_gap(&self) -> usize {
+        self.max_gap
+    }
+
+    /// # Examples
+    /// ```rust
+    /// use char_trie::tokenizer::Fillers;
+    /// let fillers = Fillers::default();
+    /// assert!(fillers.contains(' '));
+    /// assert!(fillers.contains('，'));
+    /// assert!(fillers.contains('\u{200B}'));
+    /// assert!(!fillers.contains('中'));
+    /// assert!(!fillers.contains('a'));
+    /// ```
+    pub fn contains(&self, c: char) -> bool {
+        match &self.chars {
+            Some(chars) => chars.contains(&c),
+            None => is_default_filler(c),
+        }
+    }
+}
+
+fn is_default_filler(c: char) -> bool {
+    c.is_whitespace()
+        || c.is_ascii_punctuation()
+        || matches!(c,
+            '\u{00A1}' | '\u{00A7}' | '\u{00AB}' | '\u{00B6}' | '\u{00B7}' | '\u{00BB}' | '\u{00BF}'
+            // 通用标点，包括零宽字符
+            | '\u{2000}'..='\u{206F}'
+            // CJK 标点，不含 々 〆 〇
+            | '\u{3000}'..='\u{3004}' | '\u{3008}'..='\u{3020}' | '\u{3030}'
+            | '\u{FE10}'..='\u{FE1F}' | '\u{FE30}'..='\u{FE6B}'
+            | '\u{FEFF}'
+            | '\u{FF01}'..='\u{FF0F}' | '\u{FF1A}'..='\u{FF20}' | '\u{FF3B}'..='\u{FF40}'
+            | '\u{FF5B}'..='\u{FF65}'
+        )
+}
+
+/// 需要空格分词的文字，即除中日文、泰文等连写文字以外的字母和数字
+pub fn is_spaced_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !is_unspaced_script(c)
+}
+
+/// 词与词之间不用空格分隔的文字：汉字、假名、注音、泰文、老挝文、高棉文、缅甸文
+fn is_unspaced_script(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}'       // 泰文、老挝文
+        | '\u{1000}'..='\u{109F}'     // 缅甸文
+        | '\u{1780}'..='\u{17FF}'     // 高棉文
+        | '\u{2E80}'..='\u{2FDF}'     // 部首
+        | '\u{3005}'..='\u{3007}'     // 々〆〇
+        | '\u{3040}'..='\u{312F}'     // 假名、注音
+        | '\u{31A0}'..='\u{31FF}'     // 注音扩展、片假名扩展
+        | '\u{3400}'..='\u{4DBF}'     // CJK 扩展 A
+        | '\u{4E00}'..='\u{9FFF}'     // CJK 基本区
+        | '\u{F900}'..='\u{FAFF}'     // CJK 兼容汉字
+        | '\u{FF66}'..='\u{FF9F}'     // 半角片假名
+        | '\u{20000}'..='\u{3FFFF}'   // CJK 扩展 B 及以后
+    )
+}
+
+/// 文本中 [start, end) 是否落在单词边界上：
+/// 词的首字是空格分词的文字时，前一个字符不能也是；词的尾字同理。
+/// 汉字等连写文字在任何位置都可以匹配
+/// # Examples
+/// ```rust
+/// use char_trie::tokenizer::on_word_boundary;
+/// assert!(!on_word_boundary("the", 1, 3));
+/// assert!(on_word_boundary("he said", 0, 2));
+/// assert!(on_word_boundary("T恤衫", 0, 4));
+/// assert!(on_word_boundary("中国人", 3, 6));
+/// ```
+pub fn on_word_boundary(text: &str, start: usize, end: usize) -> bool {
+    let word = &text[start..end];
+    let joined = |inner: Option<char>, outer: Option<char>| match (inner, outer) {
+        (Some(inner), Some(outer)) => is_spaced_word_char(inner) && is_spaced_word_char(outer),
+        _ => false,
+    };
+    !joined(word.chars().next(), text[..start].chars().next_back())
+        && !joined(word.chars().next_back(), text[end..].chars().next())
+}
+
+/// 词的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// 词典中的词
+    Word,
+    /// 没有被词覆盖的文本，值为 None
+    Gap,
+    /// 连续的 ASCII 字母
+    Latin,
+    /// 数字，包括小数、百分数和版本号
+    Number,
+    /// 网址
+    Url,
+    /// 连续的空白字符
+    Whitespace,
+}
+
+/// 字符分组配置，开启的字符类型在词典匹配之前先成组输出，其余字符仍然走词典匹配
+/// 成组的文本如果正好是词典中的词，同时带上词的值
+/// # Examples
+/// ```rust
+/// use char_trie::tokenizer::{CharGroups, TokenKind};
+/// use char_trie::Trie;
+/// let trie: Trie<()> = Trie::default();
+/// let tokens: Vec<_> = trie
+///     .iter_max_grouped("升级到15.0.1，详见https://a.com/v15。", CharGroups::default())
+///     .map(|(t, kind)| (t.0, kind))
+///     .collect();
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         ("升级到", TokenKind::Gap),
+///         ("15.0.1", TokenKind::Number),
+///         ("，详见", TokenKind::Gap),
+///         ("https://a.com/v15", TokenKind::Url),
+///         ("。", TokenKind::Gap),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CharGroups {
+    /// 连续的 ASCII 字母，如 `iPhone`
+    pub latin: bool,
+    /// ASCII 数字，可以带小数点和结尾的百分号，如 `6999`、`3.14`、`12.5%`，
+    /// 以及用单个点分隔的版本号，如 `15.0.1`
+    pub number: bool,
+    /// 连续的空白字符
+    pub whitespace: bool,
+    /// 以 `http://` 或 `https://` 开头的网址，结尾的标点不算在网址内
+    pub url: bool,
+}
+
+impl Default for CharGroups {
+    fn default() -> Self {
+        Self {
+            latin: true,
+            number: true,
+            whitespace: true,
+            url: true,
+        }
+    }
+}
+
+impl CharGroups {
+    /// 以 text 开头的字符组，返回 (字节长度, 类型)
+    fn group(&self, text: &str) -> Option<(usize, TokenKind)> {
+        let c = text.chars().next()?;
+        if let Some(len) = url_len(text).filter(|_| self.url) {
+            Some((len, TokenKind::Url))
+        } else if self.latin && c.is_ascii_alphabetic() {
+            let len = text
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(text.len());
+            Some((len, TokenKind::Latin))
+        } else if self.number && c.is_ascii_digit() {
+            Some((number_len(text), TokenKind::Number))
+        } else if self.whitespace && c.is_whitespace() {
+            let len = text
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(text.len());
+            Some((len, TokenKind::Whitespace))
+        } else {
+            None
+        }
+    }
+}
+
+/// 以数字开头的文本中数字的长度，小数点后必须有数字，多个小数点为版本号，可以以百分号结尾
+fn number_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let digits = |from: usize| {
+        bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+
+    let mut len = digits(0);
+    while bytes.get(len) == Some(&b'.') {
+        let fraction = digits(len + 1);
+        if fraction == 0 {
+            break;
+        }
+        len += 1 + fraction;
+    }
+    if bytes.get(len) == Some(&b'%') {
+        len += 1;
+    }
+    len
+}
+
+/// 以 http:// 或 https:// 开头的网址的长度，不是网址时返回 None
+fn url_len(text: &str) -> Option<usize> {
+    let scheme = ["http://", "https://"].into_iter().find(|scheme| {
+        text.as_bytes()
+            .get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme.as_bytes()))
+    })?;
+    let len = text
+        .find(|c: char| !c.is_ascii_alphanumeric() && !"-._~:/?#[]@!$&'()*+,;=%".contains(c))
+        .unwrap_or(text.len());
+    // 网址后紧跟的句号、逗号、括号等通常是正文的标点
+    let len = text[..len]
+        .trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '\''])
+        .len();
+    (len > scheme.len()).then_some(len)
+}
+
+/// 先按字符类型成组，其余文本做正向最大匹配，输出覆盖整个文本。
+/// 词典中的词比当前位置的字符组更长时优先输出词，混合文字的词如 `T恤`、`卡拉OK` 也能匹配
+/// # Examples
+/// ```rust
+/// use char_trie::tokenizer::{CharGroups, TokenKind};
+/// use char_trie::Trie;
+/// let mut trie = Trie::default();
+/// trie.insert("买", 1);
+/// trie.insert("T恤", 2);
+/// trie.insert("卡拉OK", 3);
+/// let tokens: Vec<_> = trie
+///     .iter_max_grouped("买T恤去卡拉OK", CharGroups::default())
+///     .map(|(t, kind)| (t.0, kind))
+///     .collect();
+/// assert_eq!(
+///     tokens,
+///     vec![
+///         ("买", TokenKind::Word),
+///         ("T恤", TokenKind::Word),
+///         ("去", TokenKind::Gap),
+///         ("卡拉OK", TokenKind::Word),
+///     ]
+/// );
+/// ```
+pub struct GroupTokenizer<'a, T> {
+    text: &'a str,
+    root: &'a Trie<T>,
+    groups: CharGroups,
+    start: usize,
+}
+
+impl<'a, T> GroupTokenizer<'a, T> {
+    pub fn new(trie: &'a Trie<T>, text: &'a str, groups: CharGroups) -> Self {
+        Self {
+            text,
+            root: trie,
+            groups,
+            start: 0,
+        }
+    }
+
+    /// 从 start 开始没有被词或字符组覆盖的文本的末尾
+    fn gap_end(&self, start: usize) -> usize {
+        let rest = &self.text[start..];
+        rest.char_indices()
+            .skip(1)
+            .find(|&(i, _)| {
+                self.groups.group(&rest[i..]).is_some()
+                    || self.root.common_prefixes(&rest[i..]).next().is_some()
+            })
+            .map_or(self.text.len(), |(i, _)| start + i)
+    }
+}
+
+impl<'a, T> Iterator for GroupTokenizer<'a, T> {
+    type Item = (Token<'a, T>, TokenKind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.text[self.start..];
+        if rest.is_empty() {
+            return None;
+        }
+
+        let start = self.start;
+        let group = self.groups.group(rest);
+        // 词典中的词比字符组长时优先
+        let word = self
+            .root
+            .longest_prefix(rest)
+            .filter(|&(len, _)| group.is_none_or(|(group, _)| len > group));
+        let (end, value, kind) = match (word, group) {
+            (Some((len, value)), _) => (start + len, Some(value), TokenKind::Word),
+            (None, Some((len, kind))) => {
+                let end = start + len;
+                (end, self.root.get(&self.text[start..end]), kind)
+            }
+            (None, None) => (self.gap_end(start), None, TokenKind::Gap),
+        };
+        self.start = end;
+        Some(((&self.text[start..end], (start, end), value), kind))
+    }
+}
+
+/// 在分词结果中补上没有被词覆盖的文本，使输出覆盖整个输入。
+/// 内部的分词结果需要按起始位置排列，允许重叠，连续的未覆盖文本作为一个 Gap 输出
+pub struct WithGaps<'a, T, I> {
+    text: &'a str,
+    inner: I,
+    /// 已经被覆盖的文本末尾
+    covered: usize,
+    pending: Option<Token<'a, T>>,
+}
+
+impl<'a, T, I> WithGaps<'a, T, I>
+where
+    I: Iterator<Item = Token<'a, T>>,
+{
+    pub fn new(text: &'a str, inner: I) -> Self {
+        Self {
+            text,
+            inner,
+            covered: 0,
+            pending: None,
+        }
+    }
+
+    fn gap(&mut self, end: usize) -> (Token<'a, T>, TokenKind) {
+        let start = self.covered;
+        self.covered = end;
+        ((&self.text[start..end], (start, end), None), TokenKind::Gap)
+    }
+}
+
+impl<'a, T, I> Iterator for WithGaps<'a, T, I>
+where
+    I: Iterator<Item = Token<'a, T>>,
+{
+    type Item = (Token<'a, T>, TokenKind);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = match self.pending.take().or_else(|| self.inner.next()) {
+            Some(token) => token,
+            None if self.covered < self.text.len() => return Some(self.gap(self.text.len())),
+            None => return None,
+        };
+
+        let (start, end) = token.1;
+        if start > self.covered {
+            self.pending = Some(token);
+            return Some(self.gap(start));
+        }
+
+        self.covered = self.covered.max(end);
+        Some((token, TokenKind::Word))
+    }
+}
+
+/// 公共前缀查询，由 [`Trie`] 以外的词典结构实现，
+/// 用于在其上实现通用的正向最大匹配和全词匹配
+pub trait PrefixSearch<'a>: Copy {
+    type Value: ?Sized + 'a;
+    type Prefixes: Iterator<Item = (usize, &'a Self::Value)>;
+
+    /// 按长度从短到长输出词典中所有是 text 前缀的词，结果为 (词在文本中的字节长度, 值)
+    fn common_prefixes(self, text: &'a str) -> Self::Prefixes;
+}
+
+/// 基于 [`PrefixSearch`] 的正向最大匹配，结果与 [`MaxFrontTokenizer`] 相同
+pub struct PrefixMaxTokenizer<'a, D> {
+    text: &'a str,
+    dict: D,
+    start: usize,
+}
+
+impl<'a, D: PrefixSearch<'a>> PrefixMaxTokenizer<'a, D> {
+    pub fn new(dict: D, text: &'a str) -> Self {
+        Self {
+            text,
+            dict,
+            start: 0,
+        }
+    }
+}
+
+impl<'a, D: PrefixSearch<'a>> Iterator for PrefixMaxTokenizer<'a, D> {
+    type Item = Token<'a, D::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = &self.text[self.start..];
+            match self.dict.common_prefixes(rest).last() {
+                Some((len, value)) => {
+                    let start = self.start;
+                    self.start += len;
+                    return Some((
+                        &self.text[start..self.start],
+                        (start, self.start),
+                        Some(value),
+                    ));
                 }
+                None => {
+                    self.start += rest.chars().next()?.len_utf8();
+                }
+            }
+        }
+    }
+}
 
-                match self.trie.char_get(c) {
-                    Some(trie) => match trie.status {
-                        Status::End => {
-                            self.end = self.end + i + c_len;
-                            self.trie = trie;
-                            return Some((
-                                &self.text[self.start..self.end],
-                                (self.start, self.end),
-                                trie.value.as_ref(),
-                            ));
-                        }
-                        Status::LastEnd => {
-                            let start = self.start;
-                            let end = self.end + i + c_len;
-                            self.start += self.first_len;
-                            self.end = self.start;
-                            self.trie = self.root;
-                            return Some((
-                                &self.text[start..end],
-                                (start, end),
-                                trie.value.as_ref(),
-                            ));
-                        }
-                        Status::Not => {
-                            self.trie = trie;
-                            continue;
-                        }
-                    },
-                    None => {
-                        break;
-                    }
+/// 基于 [`PrefixSearch`] 的全词匹配，结果与 [`AllTokenizer`] 相同
+pub struct PrefixAllTokenizer<'a, D: PrefixSearch<'a>> {
+    text: &'a str,
+    dict: D,
+    start: usize,
+    prefixes: D::Prefixes,
+}
+
+impl<'a, D: PrefixSearch<'a>> PrefixAllTokenizer<'a, D> {
+    pub fn new(dict: D, text: &'a str) -> Self {
+        Self {
+            text,
+            dict,
+            start: 0,
+            prefixes: dict.common_prefixes(text),
+        }
+    }
+}
+
+impl<'a, D: PrefixSearch<'a>> Iterator for PrefixAllTokenizer<'a, D> {
+    type Item = Token<'a, D::Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((len, value)) = self.prefixes.next() {
+                let end = self.start + len;
+                return Some((&self.text[self.start..end], (self.start, end), Some(value)));
+            }
+
+            let c = self.text[self.start..].chars().next()?;
+            self.start += c.len_utf8();
+            self.prefixes = self.dict.common_prefixes(&self.text[self.start..]);
+        }
+    }
+}
+
+/// 逆向最大匹配，需要从文本末尾开始扫描，创建时一次完成切分，然后按文本顺序输出
+pub struct MaxBackTokenizer<'a, T> {
+    tokens: std::vec::IntoIter<Token<'a, T>>,
+}
+
+impl<'a, T> MaxBackTokenizer<'a, T> {
+    pub fn new(trie: &'a ReverseTrie<T>, text: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        let mut end = text.len();
+
+        while end > 0 {
+            let rest = &text[..end];
+            match trie.longest_suffix(rest) {
+                Some((len, value)) => {
+                    let start = end - len;
+                    tokens.push((&text[start..end], (start, end), Some(value)));
+                    end = start;
+                }
+                None => {
+                    // 没有以当前字符结尾的词，跳过一个字符
+                    end -= rest.chars().next_back().map_or(0, char::len_utf8);
                 }
             }
-            self.start += self.first_len;
-            self.end = self.start;
-            self.trie = self.root;
         }
+
+        tokens.reverse();
+        Self {
+            tokens: tokens.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for MaxBackTokenizer<'a, T> {
+    type Item = Token<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.tokens.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.tokens.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for MaxBackTokenizer<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.tokens.next_back()
     }
 }
 
+impl<T> ExactSizeIterator for MaxBackTokenizer<'_, T> {}
+
 pub struct Tokenizer<'a, T> {
     text: &'a str,
     root: &'a Trie<T>,
@@ -262,7 +996,6 @@ impl<'a, T> Tokenizer<'a, T> {
     }
 
     pub fn all(&mut self) -> Option<Token<'a, T>> {
-        let mut first = true;
         loop {
             if self.start >= self.text.len() {
                 return None;
@@ -272,9 +1005,8 @@ impl<'a, T> Tokenizer<'a, T> {
 
             for (i, c) in cs {
                 let c_len = c.len_utf8();
-                if first {
+                if self.end + i == self.start {
                     self.first_len = c_len;
-                    first = false;
                 }
 
                 match self.trie.char_get(c) {
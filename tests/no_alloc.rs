@@ -0,0 +1,52 @@
+//! contains_any、find_first 和 count_matches 在热路径上使用，不能分配内存
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use char_trie::Trie;
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+#[test]
+fn match_queries_do_not_allocate() {
+    let mut trie = Trie::default();
+    for word in ["中国", "中国人", "国人", "人民", "民主", "是中"] {
+        trie.insert(word, word.len());
+    }
+    let text = "我是中国人，中国人民站起来了。".repeat(100);
+
+    assert_eq!(allocations(|| trie.contains_any(&text)), (true, 0));
+    assert_eq!(allocations(|| trie.contains_any("外国友人")), (false, 0));
+
+    let (first, n) = allocations(|| trie.find_first(&text));
+    assert_eq!(first.map(|t| t.1), Some((3, 9)));
+    assert_eq!(n, 0);
+
+    let (count, n) = allocations(|| trie.count_matches(&text));
+    assert_eq!(count, trie.iter_all(&text).count());
+    assert_eq!(n, 0);
+}